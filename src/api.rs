@@ -37,7 +37,7 @@ use uuid::Uuid;
 use crate::config::{Auth, Config};
 use crate::constants::{ARCH, EXT, PLATFORM, RELEASE_REGISTRY_LATEST_URL, VERSION};
 use crate::utils::android::AndroidManifest;
-use crate::utils::http::{self, is_absolute_url, parse_link_header};
+use crate::utils::http::{self, is_absolute_url, parse_link_header, strip_url_credentials};
 use crate::utils::progress::{make_progress_bar, ProgressBar};
 use crate::utils::retry::{get_default_backoff, DurationAsMilliseconds};
 use crate::utils::sourcemaps::get_sourcemap_reference_from_headers;
@@ -404,7 +404,8 @@ impl Api {
             )
         };
 
-        if let Some(proxy_url) = self.config.get_proxy_url() {
+        if let Some(proxy_url) = self.config.get_proxy_url_for(&url) {
+            debug!("using proxy {}", strip_url_credentials(&proxy_url));
             handle.proxy(&proxy_url)?;
         }
         if let Some(proxy_username) = self.config.get_proxy_username() {
@@ -1611,6 +1612,13 @@ impl ApiRequest {
         Ok(self)
     }
 
+    /// sets a raw request body for the request.
+    pub fn with_body(mut self, body: Vec<u8>) -> ApiResult<Self> {
+        debug!("raw body: {} bytes", body.len());
+        self.body = Some(body);
+        Ok(self)
+    }
+
     /// attaches some form data to the request.
     pub fn with_form_data(mut self, form: curl::easy::Form) -> ApiResult<Self> {
         debug!("sending form data");
@@ -1626,6 +1634,13 @@ impl ApiRequest {
         Ok(self)
     }
 
+    /// sets the maximum time the whole request may take.
+    pub fn with_timeout(mut self, timeout: std::time::Duration) -> ApiResult<Self> {
+        debug!("request timeout: {}ms", timeout.as_millis());
+        self.handle.timeout(timeout)?;
+        Ok(self)
+    }
+
     /// enables a progress bar.
     pub fn progress_bar_mode(mut self, mode: ProgressBarMode) -> ApiResult<Self> {
         self.progress_bar_mode = mode;
@@ -15,7 +15,7 @@ use username::get_user_name;
 use uuid::Uuid;
 
 use crate::config::Config;
-use crate::utils::event::{attach_logfile, get_sdk_info, with_sentry_client};
+use crate::utils::event::{attach_logfile, get_sdk_info, with_sentry_client, LogfileOptions};
 use crate::utils::releases::detect_release_name;
 
 const BASH_SCRIPT: &str = include_str!("../bashsupport.sh");
@@ -154,7 +154,14 @@ fn send_event(traceback: &str, logfile: &str, environ: bool) -> Result<(), Error
         }
     }
 
-    attach_logfile(&mut event, logfile, true)?;
+    attach_logfile(
+        &mut event,
+        logfile,
+        &LogfileOptions {
+            with_component: true,
+            ..Default::default()
+        },
+    )?;
 
     event.exception.values.push(Exception {
         ty: "BashError".into(),
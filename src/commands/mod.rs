@@ -2,15 +2,18 @@
 
 use std::env;
 use std::fmt;
+use std::path::Path;
 use std::process;
 
 use clap::{App, AppSettings, Arg, ArgMatches};
-use failure::{bail, Error};
+use failure::{bail, Error, ResultExt};
 use log::{debug, info};
+use sentry::types::Dsn;
 
 use crate::api::Api;
 use crate::config::{prepare_environment, Auth, Config};
 use crate::constants::{ARCH, PLATFORM, VERSION};
+use crate::utils::args::validate_proxy_url;
 use crate::utils::system::{print_error, QuietExit};
 use crate::utils::update::run_sentrycli_update_nagger;
 
@@ -38,6 +41,9 @@ macro_rules! each_subcommand {
         $mac!(info);
         $mac!(login);
         $mac!(send_event);
+        $mac!(send_transaction);
+        $mac!(validate_dsn);
+        $mac!(validate_event);
         $mac!(react_native);
         $mac!(difutil);
         $mac!(bash_hook);
@@ -67,6 +73,7 @@ pub mod projects;
 pub mod releases;
 pub mod repos;
 pub mod send_event;
+pub mod send_transaction;
 #[cfg(not(feature = "managed"))]
 pub mod uninstall;
 #[cfg(not(feature = "managed"))]
@@ -74,6 +81,8 @@ pub mod update;
 pub mod upload_dif;
 pub mod upload_dsym;
 pub mod upload_proguard;
+pub mod validate_dsn;
+pub mod validate_event;
 
 pub mod react_native;
 pub mod react_native_appcenter;
@@ -111,6 +120,10 @@ fn preexecute_hooks() -> Result<bool, Error> {
 }
 
 fn configure_args(config: &mut Config, matches: &ArgMatches<'_>) -> Result<(), Error> {
+    if let Some(profile) = matches.value_of("profile") {
+        config.set_profile(profile)?;
+    }
+
     if let Some(url) = matches.value_of("url") {
         config.set_base_url(url);
     }
@@ -123,6 +136,28 @@ fn configure_args(config: &mut Config, matches: &ArgMatches<'_>) -> Result<(), E
         config.set_auth(Auth::Token(auth_token.to_owned()));
     }
 
+    if let Some(dsn) = matches.value_of("dsn") {
+        let dsn = dsn.parse::<Dsn>().with_context(|_| {
+            format!(
+                "Invalid DSN ({}), expected https://<key>@<host>/<project_id>",
+                dsn
+            )
+        })?;
+        config.set_dsn(dsn);
+    }
+
+    if let Some(path) = matches.value_of("dsn_file") {
+        config.set_dsn_file(Path::new(path));
+    }
+
+    if let Some(proxy_url) = matches.value_of("http_proxy") {
+        config.set_http_proxy(proxy_url);
+    }
+
+    if let Some(proxy_url) = matches.value_of("https_proxy") {
+        config.set_https_proxy(proxy_url);
+    }
+
     if let Some(level_str) = matches.value_of("log_level") {
         match level_str.parse() {
             Ok(level) => {
@@ -191,8 +226,6 @@ impl<'a> fmt::Display for DebugArgs<'a> {
 /// Given an argument vector and a `Config` this executes the
 /// command line and returns the result.
 pub fn execute(args: &[String]) -> Result<(), Error> {
-    let mut config = Config::from_cli_config()?;
-
     // special case for the xcode integration for react native.  For more
     // information see commands/react_native_xcode.rs
     if preexecute_hooks()? {
@@ -208,6 +241,23 @@ pub fn execute(args: &[String]) -> Result<(), Error> {
         .setting(AppSettings::VersionlessSubcommands)
         .setting(AppSettings::SubcommandRequiredElseHelp)
         .global_setting(AppSettings::UnifiedHelpMessage)
+        .arg(
+            Arg::with_name("config")
+                .value_name("PATH")
+                .long("config")
+                .global(true)
+                .help("Load the config from the given file instead of the default locations."),
+        )
+        .arg(
+            Arg::with_name("profile")
+                .value_name("NAME")
+                .long("profile")
+                .global(true)
+                .help(
+                    "Use the DSN, auth token, org and project from the [profile.NAME] \
+                     section of the config file.",
+                ),
+        )
         .arg(Arg::with_name("url").value_name("URL").long("url").help(
             "Fully qualified URL to the Sentry server.{n}\
              [defaults to https://sentry.io/]",
@@ -224,6 +274,36 @@ pub fn execute(args: &[String]) -> Result<(), Error> {
                 .long("api-key")
                 .help("The given Sentry API key."),
         )
+        .arg(
+            Arg::with_name("dsn")
+                .value_name("DSN")
+                .long("dsn")
+                .global(true)
+                .help("Use the given DSN instead of the configured one."),
+        )
+        .arg(
+            Arg::with_name("dsn_file")
+                .value_name("PATH")
+                .long("dsn-file")
+                .global(true)
+                .help("Read the DSN from the given file."),
+        )
+        .arg(
+            Arg::with_name("http_proxy")
+                .value_name("URL")
+                .long("http-proxy")
+                .global(true)
+                .validator(validate_proxy_url)
+                .help("Send http requests through this proxy."),
+        )
+        .arg(
+            Arg::with_name("https_proxy")
+                .value_name("URL")
+                .long("https-proxy")
+                .global(true)
+                .validator(validate_proxy_url)
+                .help("Send https requests through this proxy."),
+        )
         .arg(
             Arg::with_name("log_level")
                 .value_name("LOG_LEVEL")
@@ -236,6 +316,12 @@ pub fn execute(args: &[String]) -> Result<(), Error> {
 
     app = add_commands(app);
     let matches = app.get_matches_from_safe(args)?;
+    // the default locations are not read at all with --config, so a broken
+    // default config file does not get in the way
+    let mut config = match matches.value_of("config") {
+        Some(path) => Config::from_path(Path::new(path))?,
+        None => Config::from_cli_config()?,
+    };
     configure_args(&mut config, &matches)?;
 
     // bind the config to the process and fetch an immutable reference to it
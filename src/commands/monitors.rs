@@ -10,6 +10,7 @@ use uuid::Uuid;
 use crate::api::{Api, CreateMonitorCheckIn, MonitorStatus, UpdateMonitorCheckIn};
 use crate::config::Config;
 use crate::utils::args::ArgExt;
+use crate::utils::event::{send_check_in, CheckIn, SendOptions};
 use crate::utils::formatting::Table;
 use crate::utils::system::QuietExit;
 
@@ -30,6 +31,47 @@ pub fn make_app<'a, 'b: 'a>(app: App<'a, 'b>) -> App<'a, 'b> {
         .setting(AppSettings::Hidden)
         .org_arg()
         .subcommand(App::new("list").about("List all monitors for an organization."))
+        .subcommand(
+            App::new("check-in")
+                .about("Send a check-in for a monitor using the DSN.")
+                .arg(
+                    Arg::with_name("monitor")
+                        .value_name("MONITOR_SLUG")
+                        .help("The slug of the monitor.")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("status")
+                        .value_name("STATUS")
+                        .long("status")
+                        .possible_values(&["in_progress", "ok", "error"])
+                        .help("The status of the job. [defaults to 'ok']"),
+                )
+                .arg(
+                    Arg::with_name("duration")
+                        .value_name("SECONDS")
+                        .long("duration")
+                        .validator(validate_duration)
+                        .help("The duration of the job in seconds."),
+                )
+                .arg(
+                    Arg::with_name("check_in_id")
+                        .value_name("ID")
+                        .long("check-in-id")
+                        .help(
+                            "Update an earlier check-in, such as an in_progress check-in \
+                             sent when the job started.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("environment")
+                        .value_name("ENVIRONMENT")
+                        .long("env")
+                        .short("E")
+                        .help("The environment of the job."),
+                ),
+        )
         .subcommand(
             App::new("run")
                 .about("Wraps a command")
@@ -55,6 +97,11 @@ pub fn make_app<'a, 'b: 'a>(app: App<'a, 'b>) -> App<'a, 'b> {
 }
 
 pub fn execute(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    // check-ins are sent with the DSN and do not need an organization
+    if let Some(sub_matches) = matches.subcommand_matches("check-in") {
+        return execute_check_in(sub_matches);
+    }
+
     let config = Config::current();
 
     let ctx = MonitorContext {
@@ -71,6 +118,36 @@ pub fn execute(matches: &ArgMatches<'_>) -> Result<(), Error> {
     unreachable!();
 }
 
+fn validate_duration(v: String) -> Result<(), String> {
+    match v.parse::<f64>() {
+        Ok(n) if n >= 0.0 => Ok(()),
+        _ => Err("Invalid duration, non-negative number of seconds required.".to_string()),
+    }
+}
+
+fn execute_check_in(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    let check_in = CheckIn {
+        check_in_id: match matches.value_of("check_in_id") {
+            Some(id) => id.parse::<Uuid>().context("invalid check-in ID")?,
+            None => Uuid::new_v4(),
+        },
+        monitor_slug: matches.value_of("monitor").unwrap().to_owned(),
+        status: match matches.value_of("status") {
+            Some("in_progress") => MonitorStatus::InProgress,
+            Some("error") => MonitorStatus::Error,
+            _ => MonitorStatus::Ok,
+        },
+        duration: matches.value_of("duration").map(str::parse).transpose()?,
+        environment: matches.value_of("environment").map(str::to_owned),
+    };
+
+    let dsn = Config::current().get_dsn()?;
+    send_check_in(&dsn, &check_in, &SendOptions::default())?;
+    println!("{}", check_in.check_in_id);
+
+    Ok(())
+}
+
 fn execute_list<'a>(ctx: &MonitorContext, _matches: &ArgMatches<'a>) -> Result<(), Error> {
     let mut monitors = ctx.api.list_organization_monitors(ctx.get_org()?)?;
     monitors.sort_by_key(|p| (p.name.clone()));
@@ -1,53 +1,483 @@
 //! Implements a command for sending events to Sentry.
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::env;
-use std::fs::File;
-use std::io::BufReader;
-use std::path::PathBuf;
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::{self, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::str;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Utc};
 use clap::{App, Arg, ArgMatches};
-use failure::{err_msg, Error};
-use glob::{glob_with, MatchOptions};
+use console::{style, user_attended};
+use failure::{bail, Error, Fail, ResultExt};
+use glob::{glob_with, MatchOptions, Pattern};
+use indicatif::HumanBytes;
 use itertools::Itertools;
-use log::warn;
-use sentry::protocol::{Event, Level, LogEntry, User};
+use lazy_static::lazy_static;
+use log::{debug, info, warn};
+use rand::Rng;
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+use regex::Regex;
+use sentry::protocol::{
+    Attachment, AttachmentType, Breadcrumb, Context, Envelope, EnvelopeItem, Event, Exception,
+    Frame, IpAddress, Level, LogEntry, Stacktrace, Thread, ThreadId, TraceContext, User,
+};
 use sentry::types::{Dsn, Uuid};
+use serde::Deserialize;
 use serde_json::Value;
+use url::Url;
 use username::get_user_name;
 
 use crate::config::Config;
-use crate::utils::args::{get_timestamp, validate_timestamp};
-use crate::utils::event::{attach_logfile, get_sdk_info, with_sentry_client};
-use crate::utils::releases::detect_release_name;
+use crate::constants::{
+    DEFAULT_CONFIRM_THRESHOLD, KNOWN_HTTP_METHODS, KNOWN_PLATFORMS, MAX_ATTACHMENT_SIZE,
+    MAX_BREADCRUMBS, MAX_MESSAGE_SIZE, MAX_TAG_VALUE_LENGTH,
+};
+use crate::utils::args::{get_timestamp, validate_timestamp, validate_uuid};
+use crate::utils::event::{
+    attach_logfile, check_event_limits, check_event_schema, get_curl_command, get_default_contexts,
+    get_envelope_event_ids, get_sdk_info, get_send_error, normalize_payload,
+    parse_category_pattern, read_schema, send_raw_envelope, serialize_envelope,
+    with_checked_sentry_client, LogfileOptions, SendOptions, DEFAULT_LOGFILE_LIMIT, EXIT_AUTH,
+    EXIT_DEADLINE, EXIT_INVALID_INPUT,
+};
+use crate::utils::event_file::{
+    get_path_pattern, parse_events, read_event_file, read_events, FileFormat,
+};
+use crate::utils::fs::get_sha1_checksum;
+use crate::utils::logging::{max_level, set_max_level};
+use crate::utils::progress::{ProgressBar, ProgressStyle};
+use crate::utils::releases::{
+    detect_environment_name, detect_release_name_with_file, read_release_file,
+    validate_environment_name,
+};
+use crate::utils::system::{get_hostname, print_error, QuietExit};
+use crate::utils::tar;
+use crate::utils::ui::prompt_to_continue;
+use crate::utils::vcs::{get_commit_summary, get_git_head, get_git_user};
+
+/// Context types with a known structure.  Other contexts are sent as is.
+const KNOWN_CONTEXT_TYPES: &[&str] = &["app", "browser", "device", "os", "runtime", "trace"];
+
+/// Suffix of messages truncated to the maximum message size.
+const TRUNCATED_SUFFIX: &str = "…[truncated]";
+
+/// Placeholder for the values of filtered environment variables.
+const FILTERED_VALUE: &str = "[Filtered]";
+
+lazy_static! {
+    /// Named placeholders in event messages, eg: `{user}`.
+    static ref MESSAGE_PARAM_RE: Regex = Regex::new(r"\{(\w+)\}").unwrap();
+    /// A stack frame in the form of `file:line:function`.
+    static ref STACKTRACE_LINE_RE: Regex =
+        Regex::new(r"^(?P<file>.+):(?P<line>\d+):(?P<function>.+)$").unwrap();
+    /// Names of environment variables that likely hold secrets.
+    static ref SENSITIVE_ENV_RE: Regex = Regex::new(
+        r"(?i)(token|secret|passw(or)?d|api_?key|access_?key|private_?key|auth|credential|session|cookie|dsn)"
+    )
+    .unwrap();
+    /// A reference to an environment variable, eg: `${HOME}`.
+    static ref ENV_VAR_RE: Regex = Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap();
+    /// The name of a variable in an env file.
+    static ref ENV_KEY_RE: Regex = Regex::new(r"^[A-Za-z_][A-Za-z0-9_.]*$").unwrap();
+    /// A `key:value` or `key=value` pair passed as argument.
+    static ref ARG_PAIR_RE: Regex = Regex::new(r"^(?P<key>[\w.-]+)[:=]").unwrap();
+    /// Values of environment variables that look like secrets, by kind.
+    static ref SECRET_VALUE_PATTERNS: Vec<(&'static str, Regex)> = SECRET_VALUE_REGEXES
+        .iter()
+        .map(|(kind, pattern)| (*kind, Regex::new(pattern).unwrap()))
+        .collect();
+}
+
+/// Patterns of secret values, such as API keys, that are sent along by
+/// accident.  Add new kinds of secrets here.
+const SECRET_VALUE_REGEXES: &[(&str, &str)] = &[
+    ("JWT", r"\beyJ[\w-]+\.eyJ[\w-]+\.[\w-]+"),
+    ("AWS access key", r"\b(AKIA|ASIA)[0-9A-Z]{16}\b"),
+    ("GitHub token", r"\bgh[pousr]_[A-Za-z0-9]{36,}\b"),
+    ("Slack token", r"\bxox[abprs]-[A-Za-z0-9-]{10,}"),
+    ("private key", r"-----BEGIN [A-Z ]*PRIVATE KEY-----"),
+];
+
+/// Arguments with credentials that are dropped with --capture-args.
+const CREDENTIAL_ARGS: &[&str] = &["--dsn", "--additional-dsn", "--auth-token", "--api-key"];
+
+/// Minimum length of values checked for high entropy.
+const MIN_SECRET_LENGTH: usize = 32;
+/// Entropy in bits per character above which a value looks like a token.
+const MIN_SECRET_ENTROPY: f64 = 4.5;
+
+/// Magic bytes at the start of minidump files.
+const MINIDUMP_MAGIC: &[u8] = b"MDMP";
 
 pub fn make_app<'a, 'b: 'a>(app: App<'a, 'b>) -> App<'a, 'b> {
     app.about("Send a manual event to Sentry.")
         .long_about(
             "Send a manual event to Sentry.{n}{n}\
              NOTE: This command will validate input parameters and attempt to send an event to \
-             Sentry. Due to network errors, rate limits or sampling the event is not guaranteed to \
-             actually arrive. Check debug output for transmission errors by passing --log-level=\
-             debug or setting `SENTRY_LOG_LEVEL=debug`.",
+             Sentry. Network errors, rate limits and server errors are retried (see --retries) and \
+             the command fails if an event could not be delivered. Due to sampling or filtering \
+             on the server the event is still not guaranteed to show up. Check debug output for \
+             transmission errors by passing --log-level=debug or setting `SENTRY_LOG_LEVEL=debug`.\
+             {n}{n}\
+             If an event could not be delivered, the command exits with code 1 if it was \
+             rejected, 2 if it was rate limited and 3 on network errors, server errors or \
+             timeouts.  Pass --ignore-send-errors to only print a warning instead.  Invalid \
+             input, such as unreadable files or events that fail validation, exits with \
+             code 4.  A missing or invalid DSN, or the server denying access, exits with \
+             code 5.  Events that were queued but not confirmed within --flush-timeout \
+             exit with code 6.  If --deadline expires before all event files were sent, \
+             the command exits with code 7.",
         )
         .arg(
             Arg::with_name("path")
                 .value_name("PATH")
                 .index(1)
                 .required(false)
-                .help("The path or glob to the file(s) in JSON format to send as event(s). When provided, all other arguments are ignored."),
+                .help("The path or glob to the file(s) in JSON format to send as event(s). Directories match all .json files in them. Gzip compressed files are decompressed automatically. When provided, arguments that build an event, such as --message or --tag, are ignored. Options such as --level, --overrides, --add-environ, --sample-rate, --min-level and --schema apply to every event read."),
+        )
+        .arg(
+            Arg::with_name("template")
+                .value_name("PATH")
+                .long("template")
+                .conflicts_with("path")
+                .help("Use an event in JSON format as template for the event.")
+                .long_help(
+                    "Use an event in JSON format as template for the event.{n}\
+                     Arguments take precedence over the template: single values such as \
+                     --level, --release, --env or --message replace the template's value, \
+                     tags, extra data, modules, contexts and user fields are merged key by \
+                     key, breadcrumbs are appended and --fingerprint replaces the template's \
+                     fingerprint.  Values that are neither in the template nor passed as \
+                     arguments are detected as usual.  The event always gets a new ID.",
+                ),
+        )
+        .arg(
+            Arg::with_name("recursive")
+                .long("recursive")
+                .requires("path")
+                .help(
+                    "When the path is a directory, also send JSON files from its \
+                     subdirectories.",
+                ),
+        )
+        .arg(
+            Arg::with_name("ndjson")
+                .long("ndjson")
+                .requires("path")
+                .help("Treat the event file(s) as newline-delimited JSON with one event per line.")
+                .long_help(
+                    "Treat the event file(s) as newline-delimited JSON with one event per line.{n}\
+                     Without this flag, files that cannot be parsed as a single JSON object are \
+                     retried as newline-delimited JSON.",
+                ),
+        )
+        .arg(
+            Arg::with_name("format")
+                .value_name("FORMAT")
+                .long("format")
+                .requires("path")
+                .conflicts_with_all(&["ndjson", "envelope"])
+                .possible_values(&["json", "yaml"])
+                .help(
+                    "The format of the event file(s).  Files ending in .yaml or .yml are \
+                     read as YAML, everything else as JSON. [defaults to detection]",
+                ),
+        )
+        .arg(
+            Arg::with_name("glob_case_insensitive")
+                .long("glob-case-insensitive")
+                .requires("path")
+                .help("Match the path glob case-insensitively."),
+        )
+        .arg(
+            Arg::with_name("envelope")
+                .long("envelope")
+                .requires("path")
+                .conflicts_with("ndjson")
+                .help(
+                    "Treat the file(s) as Sentry envelopes and forward them unchanged.  This \
+                     is the default for files with an .envelope extension.",
+                ),
+        )
+        .arg(
+            Arg::with_name("archive")
+                .long("archive")
+                .requires("path")
+                .conflicts_with_all(&["envelope", "format"])
+                .help(
+                    "Treat the file(s) as tar archives and send the events of all .json \
+                     files in them.  This is the default for files with a .tar, .tar.gz or \
+                     .tgz extension.",
+                ),
+        )
+        .arg(
+            Arg::with_name("sample_rate")
+                .value_name("RATE")
+                .long("sample-rate")
+                .requires("path")
+                .validator(validate_sample_rate)
+                .help(
+                    "The probability (0.0 to 1.0) with which each event read from files is \
+                     sent.  Events are dropped randomly on the client. [defaults to 1.0]",
+                ),
+        )
+        .arg(
+            Arg::with_name("min_level")
+                .value_name("LEVEL")
+                .long("min-level")
+                .requires("path")
+                .possible_values(&["debug", "info", "warning", "error", "fatal"])
+                .help(
+                    "Only send events read from files with at least this level.  Envelope \
+                     files are always sent.",
+                ),
+        )
+        .arg(
+            Arg::with_name("progress")
+                .long("progress")
+                .requires("path")
+                .help(
+                    "Show a progress bar while sending event files and print a summary \
+                     instead of one line per event.  Only shown on terminals without \
+                     --quiet or --output.",
+                ),
+        )
+        .arg(
+            Arg::with_name("concurrency")
+                .value_name("N")
+                .long("concurrency")
+                .requires("path")
+                .validator(validate_concurrency)
+                .help(
+                    "The number of event files to read and send in parallel.  Output is \
+                     printed in file order once all files have been processed. [defaults to 1]",
+                ),
+        )
+        .arg(
+            Arg::with_name("deadline")
+                .value_name("SECONDS")
+                .long("deadline")
+                .requires("path")
+                .validator(validate_timeout)
+                .help(
+                    "Stop sending event files after this many seconds.  Files that were \
+                     already sent are still reported and the remaining ones are skipped.",
+                ),
+        )
+        .arg(
+            Arg::with_name("fail_fast")
+                .long("fail-fast")
+                .requires("path")
+                .help(
+                    "Stop at the first event file that cannot be read or sent.  By default \
+                     remaining files are still sent and failures are reported at the end.",
+                ),
+        )
+        .arg(
+            Arg::with_name("strict_limits")
+                .long("strict-limits")
+                .help(
+                    "Fail instead of warning when tags or extra data exceed the limits \
+                     of Sentry and would be dropped or trimmed by the server.",
+                ),
+        )
+        .arg(
+            Arg::with_name("schema")
+                .value_name("PATH")
+                .long("schema")
+                .help(
+                    "Validate every event against this JSON schema before sending it.  \
+                     Violations are reported with JSON pointers and the event is not sent.  \
+                     Combine with --dry-run to lint event files.  Schemas using keywords \
+                     such as $ref or oneOf are rejected.",
+                ),
+        )
+        .arg(
+            Arg::with_name("overrides")
+                .value_name("PATH")
+                .long("overrides")
+                .requires("path")
+                .help(
+                    "Merge a JSON object with 'tags', 'extra' and 'environment' onto every \
+                     event read from files, without modifying them.  Values from this file \
+                     win over values in the events.  Envelopes are sent unchanged.",
+                ),
+        )
+        .arg(
+            Arg::with_name("dry_run")
+                .long("dry-run")
+                .help("Print the event(s) as JSON instead of sending them to Sentry.")
+                .long_help(
+                    "Build and validate the event(s) and print them as JSON instead of \
+                     sending them to Sentry.  The command fails if any event cannot be \
+                     parsed which makes this usable as a pre-commit check.",
+                ),
+        )
+        .arg(
+            Arg::with_name("simulate_rate_limit")
+                .value_name("COUNT")
+                .long("simulate-rate-limit")
+                .requires_all(&["dry_run", "path"])
+                .validator(validate_count)
+                .help(
+                    "With --dry-run, report how many events would be dropped if only this \
+                     many events per second were accepted.  Events are grouped by the \
+                     second of their timestamp.",
+                ),
+        )
+        .arg(
+            Arg::with_name("print_curl")
+                .value_name("MODE")
+                .long("print-curl")
+                .min_values(0)
+                .require_equals(true)
+                .possible_values(&["full"])
+                .conflicts_with("path")
+                .help("Print a curl command that sends the event instead of sending it.")
+                .long_help(
+                    "Print a curl command that posts the event to the envelope endpoint of \
+                     the DSN instead of sending it, eg: for support tickets.  Combine with \
+                     --dry-run to also print the event.  The keys in the auth header are \
+                     redacted unless --print-curl=full is given.",
+                ),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .long("verbose")
+                .short("v")
+                .help("Log the DSN host, a summary of each event and the send result.")
+                .long_help(
+                    "Log the DSN host, a summary of each event and the send result at info \
+                     level, without the noise of --log-level=debug.  Secrets such as the \
+                     DSN key are never logged.  When sending more than one event file, also \
+                     print every dispatched event instead of only the summary.",
+                ),
+        )
+        .arg(
+            Arg::with_name("quiet")
+                .long("quiet")
+                .short("q")
+                .conflicts_with("output")
+                .help("Only print the event ID(s) to stdout.")
+                .long_help(
+                    "Only print the bare event ID(s) to stdout, one per line.  \
+                     Combined with --dry-run, the IDs of the events that would be sent \
+                     are printed.",
+                ),
+        )
+        .arg(
+            Arg::with_name("output")
+                .value_name("FORMAT")
+                .long("output")
+                .possible_values(&["text", "json"])
+                .help("The output format. [defaults to 'text']")
+                .long_help(
+                    "The output format. [defaults to 'text']{n}\
+                     With 'json', one JSON object per event is printed to stdout \
+                     (eg: {\"id\": \"...\"}).  Combined with --dry-run, the object also \
+                     contains the full event under the \"event\" key.",
+                ),
+        )
+        .arg(
+            Arg::with_name("retries")
+                .value_name("COUNT")
+                .long("retries")
+                .validator(validate_count)
+                .help("How often to retry sending an event after a transient failure. [defaults to 3]"),
+        )
+        .arg(
+            Arg::with_name("timeout")
+                .value_name("SECONDS")
+                .long("timeout")
+                .validator(validate_timeout)
+                .help(
+                    "The maximum time in seconds to spend sending each event, including all \
+                     retries. [defaults to 30]",
+                ),
+        )
+        .arg(
+            Arg::with_name("flush_timeout")
+                .value_name("SECONDS")
+                .long("flush-timeout")
+                .validator(validate_timeout)
+                .help(
+                    "The maximum time in seconds to wait for queued events to be confirmed \
+                     before exiting.  Independent of --timeout, which limits the requests.  \
+                     [defaults to waiting until all events are sent]",
+                ),
+        )
+        .arg(
+            Arg::with_name("compress")
+                .long("compress")
+                .help("Compress events with gzip before sending them."),
+        )
+        .arg(
+            Arg::with_name("relay_url")
+                .value_name("URL")
+                .long("relay-url")
+                .validator(validate_relay_url)
+                .help(
+                    "Send events to this Relay or proxy instead of the host of the DSN.  \
+                     The project and public key of the DSN are still used.",
+                ),
+        )
+        .arg(
+            Arg::with_name("ignore_send_errors")
+                .long("ignore-send-errors")
+                .help(
+                    "Print a warning instead of failing if an event could not be delivered.  \
+                     Invalid arguments or unreadable files still fail the command.",
+                ),
+        )
+        .arg(
+            Arg::with_name("retry_delay")
+                .value_name("MS")
+                .long("retry-delay")
+                .validator(validate_count)
+                .help(
+                    "The delay in milliseconds before the first retry.  Subsequent retries \
+                     back off exponentially. [defaults to 1000]",
+                ),
         )
         .arg(
             Arg::with_name("level")
                 .value_name("LEVEL")
                 .long("level")
                 .short("l")
-                .help("Optional event severity/log level. (debug|info|warning|error|fatal) [defaults to 'error']"),
+                .help("Optional event severity/log level. (debug|info|warning|error|fatal) [defaults to 'error']")
+                .long_help(
+                    "Optional event severity/log level. (debug|info|warning|error|fatal) \
+                     [defaults to 'error']{n}\
+                     When sending event files, this overrides the level of every event. \
+                     Without it, the level stored in the files is preserved.",
+                ),
+        )
+        .arg(
+            Arg::with_name("sdk_name")
+                .value_name("NAME")
+                .long("sdk-name")
+                .help("Report this SDK name in the event. [defaults to 'sentry-cli']"),
+        )
+        .arg(
+            Arg::with_name("sdk_version")
+                .value_name("VERSION")
+                .long("sdk-version")
+                .help("Report this SDK version in the event. [defaults to the sentry-cli version]"),
         )
         .arg(Arg::with_name("timestamp")
                  .long("timestamp")
                  .validator(validate_timestamp)
+                 .allow_hyphen_values(true)
                  .value_name("TIMESTAMP")
-                 .help("Optional event timestamp in one of supported formats: unix timestamp, RFC2822 or RFC3339."))
+                 .help("Optional event timestamp in one of supported formats: unix timestamp, RFC2822, RFC3339 \
+                        or relative to now (eg: -5m, -2h, -1d)."))
         .arg(
             Arg::with_name("release")
                 .value_name("RELEASE")
@@ -55,6 +485,49 @@ pub fn make_app<'a, 'b: 'a>(app: App<'a, 'b>) -> App<'a, 'b> {
                 .short("r")
                 .help("Optional identifier of the release."),
         )
+        .arg(
+            Arg::with_name("release_file")
+                .value_name("PATH")
+                .long("release-file")
+                .conflicts_with("release")
+                .help(
+                    "Read the release from a file.  Without --release or --release-file, the \
+                     release is read from SENTRY_RELEASE or detected from the environment, \
+                     a VERSION file in the working directory or git.",
+                ),
+        )
+        .arg(
+            Arg::with_name("no_release_detection")
+                .long("no-release-detection")
+                .help(
+                    "Do not detect the release from the environment, a VERSION file or git \
+                     and send the event without release.  --release and --release-file are \
+                     still used.",
+                ),
+        )
+        .arg(
+            Arg::with_name("commit")
+                .value_name("SHA")
+                .long("commit")
+                .help(
+                    "Link the event to a commit.  Sets the commit tag and adds the commit \
+                     with its message from the local git repository, if found, as extra data.",
+                ),
+        )
+        .arg(
+            Arg::with_name("git_enrich")
+                .long("git-enrich")
+                .help(
+                    "Add the current branch and commit of the local git repository as \
+                     git.branch and git.commit tags.  Skipped outside of a git repository.",
+                ),
+        )
+        .arg(
+            Arg::with_name("strict_git")
+                .long("strict-git")
+                .requires("git_enrich")
+                .help("Fail if --git-enrich cannot read the local git repository."),
+        )
         .arg(
             Arg::with_name("dist")
                 .value_name("DISTRIBUTION")
@@ -62,18 +535,203 @@ pub fn make_app<'a, 'b: 'a>(app: App<'a, 'b>) -> App<'a, 'b> {
                 .short("d")
                 .help("Set the distribution."),
         )
+        .arg(
+            Arg::with_name("event_id")
+                .value_name("UUID")
+                .long("event-id")
+                .validator(validate_uuid)
+                .conflicts_with_all(&["path", "repeat"])
+                .help("Use this event ID instead of generating a new one."),
+        )
+        .arg(
+            Arg::with_name("trace_id")
+                .value_name("TRACE_ID")
+                .long("trace-id")
+                .validator(|v| validate_hex_id(&v, 32, "trace id"))
+                .help("Connect the event to a trace with this 32 character hex id."),
+        )
+        .arg(
+            Arg::with_name("parent_span_id")
+                .value_name("SPAN_ID")
+                .long("parent-span-id")
+                .requires("trace_id")
+                .validator(|v| validate_hex_id(&v, 16, "span id"))
+                .help("Attach the event below this 16 character hex span id in the trace."),
+        )
+        .arg(
+            Arg::with_name("transaction_name")
+                .value_name("NAME")
+                .long("transaction-name")
+                .help(
+                    "Attribute the event to a transaction, such as the route or task that \
+                     failed.  This only sets the transaction name of the error event, use \
+                     send-transaction to send performance data.",
+                ),
+        )
+        .arg(
+            Arg::with_name("culprit")
+                .value_name("VALUE")
+                .long("culprit")
+                .help(
+                    "Set the deprecated culprit of the event, describing where the issue \
+                     occurred.  Sentry replaced it with the transaction name, prefer \
+                     --transaction-name unless your server still relies on the culprit.",
+                ),
+        )
         .arg(
             Arg::with_name("environment")
                 .value_name("ENVIRONMENT")
                 .long("env")
                 .short("E")
-                .help("Send with a specific environment."),
+                .validator(|v| {
+                    validate_environment_name(&v)
+                        .map(|_| ())
+                        .map_err(|err| err.to_string())
+                })
+                .help(
+                    "Send with a specific environment.  This defaults to SENTRY_ENVIRONMENT \
+                     or, when running on CI, to the branch name or 'ci'.",
+                ),
+        )
+        .arg(
+            Arg::with_name("environment_file")
+                .value_name("PATH")
+                .long("environment-file")
+                .help(
+                    "Read the environment from a file, such as one written by a deploy \
+                     step.  --env takes precedence over this.",
+                ),
+        )
+        .arg(
+            Arg::with_name("no_detect_environment")
+                .long("no-detect-environment")
+                .conflicts_with("environment")
+                .help("Do not detect the environment from SENTRY_ENVIRONMENT or CI environment variables"),
+        )
+        .arg(
+            Arg::with_name("server_name")
+                .value_name("NAME")
+                .long("server-name")
+                .help("Set the server name. [defaults to the hostname]"),
+        )
+        .arg(
+            Arg::with_name("no_server_name")
+                .long("no-server-name")
+                .conflicts_with("server_name")
+                .help("Do not send the hostname as server name along"),
+        )
+        .arg(
+            Arg::with_name("env_file")
+                .value_name("PATH")
+                .long("env-file")
+                .help(
+                    "Load environment variables from a file with KEY=VALUE lines before \
+                     building the event, so that they are sent with the environ and used \
+                     for release detection.  Supports quotes and '#' comments like .env \
+                     files.  Variables that are already set are kept.",
+                ),
+        )
+        .arg(
+            Arg::with_name("env_file_override")
+                .long("env-file-override")
+                .requires("env_file")
+                .help("Overwrite variables that are already set with those from --env-file."),
         )
         .arg(
             Arg::with_name("no_environ")
                 .long("no-environ")
                 .help("Do not send environment variables along"),
         )
+        .arg(
+            Arg::with_name("no_user")
+                .long("no-user")
+                .conflicts_with_all(&["user_data", "no_git_user"])
+                .help("Do not send any user information, including the OS user and IP address."),
+        )
+        .arg(
+            Arg::with_name("environ_allowlist")
+                .value_name("NAME")
+                .long("environ-allowlist")
+                .multiple(true)
+                .number_of_values(1)
+                .conflicts_with("no_environ")
+                .validator(validate_environ_pattern)
+                .help(
+                    "Only send environment variables matching this name or wildcard pattern \
+                     (eg: 'CI_*').  Can be repeated.",
+                ),
+        )
+        .arg(
+            Arg::with_name("add_environ")
+                .long("add-environ")
+                .requires("path")
+                .conflicts_with("no_environ")
+                .help(
+                    "Add environment variables to events read from files, like they are \
+                     added to manual events.  Events that already have them are left as is.",
+                ),
+        )
+        .arg(
+            Arg::with_name("strict_secrets")
+                .long("strict-secrets")
+                .conflicts_with("no_environ")
+                .help(
+                    "Fail instead of warning when values of environment variables look \
+                     like secrets, such as JWTs, AWS keys or high-entropy tokens.",
+                ),
+        )
+        .arg(
+            Arg::with_name("environ_denylist")
+                .value_name("NAME")
+                .long("environ-denylist")
+                .multiple(true)
+                .number_of_values(1)
+                .conflicts_with("no_environ")
+                .validator(validate_environ_pattern)
+                .help(
+                    "Do not send environment variables matching this name or wildcard \
+                     pattern.  Can be repeated.  Values of variables that look like secrets \
+                     (tokens, keys, passwords) are always replaced with [Filtered].",
+                ),
+        )
+        .arg(
+            Arg::with_name("disable_environ_for_keys")
+                .value_name("KEYS")
+                .long("disable-environ-for-keys")
+                .multiple(true)
+                .number_of_values(1)
+                .use_delimiter(true)
+                .help(
+                    "Do not send these comma-separated environment variables, without \
+                     wildcards.  Has no effect when environment variables are not sent.",
+                ),
+        )
+        .arg(
+            Arg::with_name("no_contexts")
+                .long("no-contexts")
+                .help("Do not send os and runtime contexts along"),
+        )
+        .arg(
+            Arg::with_name("no_default_tags")
+                .long("no-default-tags")
+                .conflicts_with("add_environ")
+                .help(
+                    "Only send explicitly provided fields and SDK information.  This \
+                     disables the environ extra, the os and runtime contexts, the hostname \
+                     as server name, the OS or git user and the detected release and \
+                     environment.",
+                ),
+        )
+        .arg(
+            Arg::with_name("contexts_file")
+                .value_name("PATH")
+                .long("contexts-file")
+                .help(
+                    "Read additional contexts from a JSON object file, where each key is \
+                     the name of a context and each value an object.  These are merged \
+                     with the os and runtime contexts.",
+                ),
+        )
         .arg(
             Arg::with_name("message")
                 .value_name("MESSAGE")
@@ -83,6 +741,38 @@ pub fn make_app<'a, 'b: 'a>(app: App<'a, 'b>) -> App<'a, 'b> {
                 .number_of_values(1)
                 .help("The event message."),
         )
+        .arg(
+            Arg::with_name("expand_env")
+                .value_name("MODE")
+                .long("expand-env")
+                .min_values(0)
+                .require_equals(true)
+                .possible_values(&["lenient"])
+                .help("Expand ${VAR} in the values of --message, --tag and --extra.")
+                .long_help(
+                    "Expand environment variables written as ${VAR} in the values of \
+                     --message, --tag and --extra, eg: where no shell expands them.  \
+                     Undefined variables are an error, unless --expand-env=lenient is \
+                     given which expands them to an empty string.",
+                ),
+        )
+        .arg(
+            Arg::with_name("max_message_size")
+                .value_name("BYTES")
+                .long("max-message-size")
+                .validator(validate_count)
+                .help(
+                    "Truncate the event message to this many bytes instead of leaving it to \
+                     the server. [defaults to 8192]",
+                ),
+        )
+        .arg(
+            Arg::with_name("message_file")
+                .value_name("PATH")
+                .long("message-file")
+                .conflicts_with("message")
+                .help("Read the event message from a file, or from stdin if the path is '-'."),
+        )
         .arg(
             Arg::with_name("message_args")
                 .value_name("MESSAGE_ARG")
@@ -92,6 +782,18 @@ pub fn make_app<'a, 'b: 'a>(app: App<'a, 'b>) -> App<'a, 'b> {
                 .number_of_values(1)
                 .help("Arguments for the event message."),
         )
+        .arg(
+            Arg::with_name("message_params")
+                .value_name("NAME=VALUE")
+                .long("message-param")
+                .multiple(true)
+                .number_of_values(1)
+                .conflicts_with("message_args")
+                .help(
+                    "Named parameter for '{NAME}' placeholders in the event message.  The \
+                     message is sent as template so that events group by it.",
+                ),
+        )
         .arg(
             Arg::with_name("platform")
                 .value_name("PLATFORM")
@@ -99,6 +801,18 @@ pub fn make_app<'a, 'b: 'a>(app: App<'a, 'b>) -> App<'a, 'b> {
                 .short("p")
                 .help("Override the default 'other' platform specifier."),
         )
+        .arg(
+            Arg::with_name("strict_platform")
+                .long("strict-platform")
+                .requires("platform")
+                .help("Fail if the platform is not one of the identifiers known to Sentry."),
+        )
+        .arg(
+            Arg::with_name("logger")
+                .value_name("NAME")
+                .long("logger")
+                .help("Set the logger name, eg: to filter events sent from scripts."),
+        )
         .arg(
             Arg::with_name("tags")
                 .value_name("KEY:VALUE")
@@ -109,26 +823,160 @@ pub fn make_app<'a, 'b: 'a>(app: App<'a, 'b>) -> App<'a, 'b> {
                 .help("Add a tag (key:value) to the event."),
         )
         .arg(
-            Arg::with_name("extra")
-                .value_name("KEY:VALUE")
-                .long("extra")
-                .short("e")
+            Arg::with_name("tag_from_env")
+                .value_name("PREFIX")
+                .long("tag-from-env")
                 .multiple(true)
                 .number_of_values(1)
-                .help("Add extra information (key:value) to the event."),
+                .help(
+                    "Add environment variables starting with this prefix as tags, with the \
+                     prefix removed and the name lowercased (eg: 'CI_' turns CI_BRANCH=main \
+                     into branch:main).  The environ allowlist and denylist apply and \
+                     variables that look like secrets are skipped.  Tags passed with --tag \
+                     or --tags-file take precedence.",
+                ),
         )
         .arg(
-            Arg::with_name("user_data")
-                .value_name("KEY:VALUE")
-                .long("user")
-                .short("u")
-                .multiple(true)
-                .number_of_values(1)
+            Arg::with_name("delimiter")
+                .value_name("CHAR")
+                .long("tag-delimiter")
+                .validator(validate_delimiter)
                 .help(
-                    "Add user information (key:value) to the event. \
+                    "The delimiter separating keys from values in --tag, --tags-file, \
+                     --module, --modules-file, --extra, --user and --request-header pairs.  \
+                     Values may contain the delimiter, and keys can contain it when escaped \
+                     with a backslash. [defaults to ':']",
+                ),
+        )
+        .arg(
+            Arg::with_name("tags_file")
+                .value_name("PATH")
+                .long("tags-file")
+                .help(
+                    "Read tags (key:value) from a file, one per line.  Lines starting with \
+                     '#' are ignored.  Tags passed with --tag take precedence.",
+                ),
+        )
+        .arg(
+            Arg::with_name("tags_json")
+                .value_name("JSON")
+                .long("tags-json")
+                .help(
+                    "Add tags from a JSON object (eg: '{\"team\": \"core\"}').  Values \
+                     other than strings are converted to strings.  These take precedence \
+                     over --tags-file, and tags passed with --tag take precedence over \
+                     these.",
+                ),
+        )
+        .arg(
+            Arg::with_name("strict_tags")
+                .long("strict-tags")
+                .requires("tags_json")
+                .help("Fail if --tags-json contains values that are not strings."),
+        )
+        .arg(
+            Arg::with_name("truncate_tags")
+                .long("truncate-tags")
+                .help(
+                    "Truncate tag values longer than 200 characters instead of letting \
+                     Sentry drop the data.  Prints a warning for every truncated tag.",
+                ),
+        )
+        .arg(
+            Arg::with_name("modules")
+                .value_name("NAME:VERSION")
+                .long("module")
+                .multiple(true)
+                .number_of_values(1)
+                .help("Add a loaded module or dependency (name:version) to the event."),
+        )
+        .arg(
+            Arg::with_name("modules_file")
+                .value_name("PATH")
+                .long("modules-file")
+                .help(
+                    "Read modules (name:version) from a file, one per line.  Lines starting \
+                     with '#' are ignored.  Modules passed with --module take precedence.",
+                ),
+        )
+        .arg(
+            Arg::with_name("extra")
+                .value_name("KEY:VALUE")
+                .long("extra")
+                .short("e")
+                .multiple(true)
+                .number_of_values(1)
+                .help("Add extra information (key:value) to the event."),
+        )
+        .arg(
+            Arg::with_name("infer_extra_types")
+                .long("infer-extra-types")
+                .requires("extra")
+                .help(
+                    "Send --extra values that look like numbers or booleans as such, so \
+                     that they can be queried numerically (eg: 'retries:42').  Values \
+                     wrapped in double quotes stay strings (eg: 'retries:\"42\"').",
+                ),
+        )
+        .arg(
+            Arg::with_name("extra_json")
+                .value_name("KEY:JSON")
+                .long("extra-json")
+                .multiple(true)
+                .number_of_values(1)
+                .help(
+                    "Add extra information to the event with a JSON value, preserving \
+                     objects and arrays (eg: 'build:{\"id\": 42}').",
+                ),
+        )
+        .arg(
+            Arg::with_name("user_data")
+                .value_name("KEY:VALUE")
+                .long("user")
+                .short("u")
+                .multiple(true)
+                .number_of_values(1)
+                .help(
+                    "Add user information (key:value) to the event. \
                      [eg: id:42, username:foo]",
                 ),
         )
+        .arg(
+            Arg::with_name("no_git_user")
+                .long("no-git-user")
+                .conflicts_with("user_data")
+                .help(
+                    "Do not read the user from the git config (user.name and user.email), \
+                     only send the name of the OS user.",
+                ),
+        )
+        .arg(
+            Arg::with_name("request_url")
+                .value_name("URL")
+                .long("request-url")
+                .validator(validate_request_url)
+                .help("Set the URL of the HTTP request the event is about."),
+        )
+        .arg(
+            Arg::with_name("request_method")
+                .value_name("METHOD")
+                .long("request-method")
+                .help("Set the method of the HTTP request the event is about, eg: GET."),
+        )
+        .arg(
+            Arg::with_name("strict_request_method")
+                .long("strict-request-method")
+                .requires("request_method")
+                .help("Fail if the request method is not a known HTTP method."),
+        )
+        .arg(
+            Arg::with_name("request_headers")
+                .value_name("KEY:VALUE")
+                .long("request-header")
+                .multiple(true)
+                .number_of_values(1)
+                .help("Add a header (key:value) to the HTTP request the event is about."),
+        )
         .arg(
             Arg::with_name("fingerprint")
                 .value_name("FINGERPRINT")
@@ -136,13 +984,57 @@ pub fn make_app<'a, 'b: 'a>(app: App<'a, 'b>) -> App<'a, 'b> {
                 .short("f")
                 .multiple(true)
                 .number_of_values(1)
-                .help("Change the fingerprint of the event."),
+                .help("Change the fingerprint of the event.")
+                .long_help(
+                    "Change the fingerprint of the event.  Can be repeated to build a \
+                     fingerprint from multiple values.{n}\
+                     Pass '{{ default }}' as one of the values to group by Sentry's default \
+                     grouping plus a custom key, eg: -f '{{ default }}' -f mykey.",
+                ),
+        )
+        .arg(
+            Arg::with_name("fingerprint_from_file")
+                .value_name("PATH")
+                .long("fingerprint-from-file")
+                .help(
+                    "Append the SHA1 checksum of a file to the fingerprint of the event.  \
+                     This can be combined with --fingerprint.",
+                ),
         )
         .arg(
             Arg::with_name("logfile")
                 .value_name("PATH")
                 .long("logfile")
-                .help("Send a logfile as breadcrumbs with the event (last 100 records)"),
+                .multiple(true)
+                .number_of_values(1)
+                .help(
+                    "Send a logfile as breadcrumbs with the event (see --logfile-limit).  \
+                     Can be repeated to merge multiple logfiles in the given order.",
+                ),
+        )
+        .arg(
+            Arg::with_name("logfile_limit")
+                .value_name("N")
+                .long("logfile-limit")
+                .requires("logfile")
+                .validator(validate_count)
+                .help(
+                    "The number of records to send from the end of each logfile, 0 sends \
+                     all records.  The combined number of breadcrumbs is capped with \
+                     --max-breadcrumbs. [defaults to 100]",
+                ),
+        )
+        .arg(
+            Arg::with_name("breadcrumbs_since")
+                .value_name("TIMESTAMP")
+                .long("breadcrumbs-since")
+                .requires("logfile")
+                .validator(validate_timestamp)
+                .help(
+                    "Drop logfile records with a timestamp before this one, as a Unix \
+                     timestamp, ISO 8601 date or relative time (eg: -1h).  Records without \
+                     a timestamp are kept.",
+                ),
         )
         .arg(
             Arg::with_name("with_categories")
@@ -154,105 +1046,2252 @@ pub fn make_app<'a, 'b: 'a>(app: App<'a, 'b>) -> App<'a, 'b> {
                     eg. \"INFO: Something broke\" will be parsed as a breadcrumb \
                     \"{\"level\": \"info\", \"message\": \"Something broke\"}\"")
         )
+        .arg(
+            Arg::with_name("category_levels")
+                .value_name("CATEGORY=LEVEL")
+                .long("category-level-map")
+                .multiple(true)
+                .number_of_values(1)
+                .requires("with_categories")
+                .validator(validate_category_level)
+                .help(
+                    "Use this level for breadcrumbs of a logfile category parsed with \
+                     --with-categories (eg: 'WARN=warning').  Can be repeated.  Other \
+                     categories that name a log level use that level, everything else is \
+                     sent as info.",
+                ),
+        )
+        .arg(
+            Arg::with_name("breadcrumbs")
+                .value_name("BREADCRUMB")
+                .long("breadcrumb")
+                .multiple(true)
+                .number_of_values(1)
+                .validator(validate_breadcrumb)
+                .help(
+                    "Add a breadcrumb in the form of 'level:category:message' or as JSON \
+                     object.  Can be repeated and is added after breadcrumbs from --logfile.",
+                ),
+        )
+        .arg(
+            Arg::with_name("max_breadcrumbs")
+                .value_name("N")
+                .long("max-breadcrumbs")
+                .validator(validate_count)
+                .help(
+                    "The maximum number of breadcrumbs from --logfile and --breadcrumb \
+                     combined.  The oldest breadcrumbs are dropped first, 0 keeps all. \
+                     [defaults to 100, or to 0 with --logfile-limit 0]",
+                ),
+        )
+        .arg(
+            Arg::with_name("category_pattern")
+                .value_name("REGEX")
+                .long("category-pattern")
+                .requires("logfile")
+                .validator(validate_category_pattern)
+                .help(
+                    "Extract breadcrumbs from logfile records with a regular expression \
+                     containing the named groups 'level' and 'message' and optionally \
+                     'category'.  Records not matching the pattern are sent unchanged.",
+                ),
+        )
+        .arg(
+            Arg::with_name("stacktrace_file")
+                .value_name("PATH")
+                .long("stacktrace-file")
+                .help(
+                    "Attach a stacktrace to the event as exception.  The file contains either \
+                     a JSON array of frames or one 'file:line:function' frame per line, from \
+                     the outermost to the innermost call.",
+                ),
+        )
+        .arg(
+            Arg::with_name("threads")
+                .value_name("ID:NAME[:crashed]")
+                .long("thread")
+                .multiple(true)
+                .number_of_values(1)
+                .help(
+                    "Add a thread to the event, eg: '1:main:crashed'.  Append ':crashed' \
+                     to mark the thread that crashed.  Can be repeated with unique ids.",
+                ),
+        )
+        .arg(
+            Arg::with_name("attachments")
+                .value_name("PATH")
+                .long("attachment")
+                .multiple(true)
+                .number_of_values(1)
+                .help("Attach a file to the event.  Files larger than 20MB are skipped."),
+        )
+        .arg(
+            Arg::with_name("attachment_strings")
+                .value_name("NAME=CONTENT")
+                .long("attachment-string")
+                .multiple(true)
+                .number_of_values(1)
+                .help(
+                    "Attach text to the event as a file with the given name.  The name ends \
+                     at the first '=', so the content may contain '='.  Content larger than \
+                     20MB is skipped.",
+                ),
+        )
+        .arg(
+            Arg::with_name("minidump")
+                .value_name("PATH")
+                .long("minidump")
+                .help(
+                    "Attach a minidump to the event.  Sentry processes the event as a \
+                     native crash from the minidump.",
+                ),
+        )
+        .arg(
+            Arg::with_name("repeat")
+                .value_name("COUNT")
+                .long("repeat")
+                .validator(validate_repeat)
+                .help(
+                    "Send the event this many times, each with a new event ID.  More than \
+                     --confirm-threshold repetitions need to be confirmed.",
+                ),
+        )
+        .arg(
+            Arg::with_name("repeat_delay")
+                .value_name("MS")
+                .long("repeat-delay")
+                .requires("repeat")
+                .validator(validate_count)
+                .help("The delay in milliseconds between repeated events."),
+        )
+        .arg(
+            Arg::with_name("confirm_repeat")
+                .long("confirm-repeat")
+                .requires("repeat")
+                .help(
+                    "Allow sending the event more than --confirm-threshold times with \
+                     --repeat without asking.",
+                ),
+        )
+        .arg(
+            Arg::with_name("yes")
+                .long("yes")
+                .short("y")
+                .help(
+                    "Do not ask for confirmation before sending more events or files than \
+                     --confirm-threshold.  Without a terminal such sends fail unless this \
+                     is passed.",
+                ),
+        )
+        .arg(
+            Arg::with_name("confirm_threshold")
+                .value_name("COUNT")
+                .long("confirm-threshold")
+                .validator(validate_count)
+                .help(
+                    "Ask for confirmation when sending more event files or repetitions than \
+                     this. [defaults to 100]",
+                ),
+        )
+        .arg(
+            Arg::with_name("capture_args")
+                .long("capture-args")
+                .help(
+                    "Send the command line of sentry-cli along as the 'command' tag and \
+                     the 'command_line' extra.  DSNs and auth tokens are left out and \
+                     values that look like secrets are replaced with [Filtered].",
+                ),
+        )
+        .arg(
+            Arg::with_name("additional_dsns")
+                .value_name("DSN")
+                .long("additional-dsn")
+                .multiple(true)
+                .number_of_values(1)
+                .validator(validate_dsn)
+                .help(
+                    "Also send the event to the given DSN.  This can be repeated.  The \
+                     command only fails if the event could not be sent to any DSN.",
+                ),
+        )
+}
+
+fn validate_delimiter(v: String) -> Result<(), String> {
+    if v.chars().count() == 1 {
+        Ok(())
+    } else {
+        Err("The delimiter must be a single character.".to_string())
+    }
+}
+
+fn validate_sample_rate(v: String) -> Result<(), String> {
+    match v.parse::<f64>() {
+        Ok(rate) if (0.0..=1.0).contains(&rate) => Ok(()),
+        _ => Err("Invalid sample rate, number between 0.0 and 1.0 required.".to_string()),
+    }
+}
+
+fn validate_concurrency(v: String) -> Result<(), String> {
+    match v.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(()),
+        _ => Err("Invalid concurrency, positive integer required.".to_string()),
+    }
+}
+
+fn validate_timeout(v: String) -> Result<(), String> {
+    match v.parse::<u64>() {
+        Ok(n) if n > 0 => Ok(()),
+        _ => Err("Invalid timeout, positive number of seconds required.".to_string()),
+    }
+}
+
+fn validate_repeat(v: String) -> Result<(), String> {
+    match v.parse::<u32>() {
+        Ok(n) if n > 0 => Ok(()),
+        _ => Err("Invalid repeat count, positive integer required.".to_string()),
+    }
+}
+
+fn validate_breadcrumb(v: String) -> Result<(), String> {
+    parse_breadcrumb(&v).map(|_| ()).map_err(|e| e.to_string())
+}
+
+fn validate_category_level(v: String) -> Result<(), String> {
+    parse_category_level(&v)
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
+fn validate_category_pattern(v: String) -> Result<(), String> {
+    parse_category_pattern(&v)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+fn validate_environ_pattern(v: String) -> Result<(), String> {
+    Pattern::new(&v)
+        .map(|_| ())
+        .map_err(|e| format!("Invalid pattern: {}", e))
+}
+
+fn validate_hex_id(v: &str, len: usize, kind: &str) -> Result<(), String> {
+    if v.len() == len && v.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid {}, {} hex characters required.",
+            kind, len
+        ))
+    }
+}
+
+fn validate_count(v: String) -> Result<(), String> {
+    if v.parse::<u32>().is_ok() {
+        Ok(())
+    } else {
+        Err("Invalid number, non-negative integer required.".to_string())
+    }
+}
+
+fn validate_relay_url(v: String) -> Result<(), String> {
+    match Url::parse(&v) {
+        Ok(url) if (url.scheme() == "http" || url.scheme() == "https") && url.has_host() => Ok(()),
+        Ok(_) => Err("Invalid relay URL, expected an http or https URL".to_string()),
+        Err(err) => Err(format!("Invalid relay URL: {}", err)),
+    }
+}
+
+fn validate_request_url(v: String) -> Result<(), String> {
+    match Url::parse(&v) {
+        Ok(_) => Ok(()),
+        Err(err) => Err(format!("Invalid request URL: {}", err)),
+    }
+}
+
+fn validate_dsn(v: String) -> Result<(), String> {
+    match v.parse::<Dsn>() {
+        Ok(_) => Ok(()),
+        Err(err) => Err(format!(
+            "{}, expected https://<key>@<host>/<project_id>",
+            err
+        )),
+    }
+}
+
+/// Collects environment variables to send with the event.  Values of
+/// variables that look like they contain secrets are filtered.
+fn get_environ(allowlist: &[Pattern], denylist: &[Pattern]) -> Value {
+    let matches_any = |patterns: &[Pattern], key: &str| patterns.iter().any(|p| p.matches(key));
+
+    Value::Object(
+        env::vars()
+            .filter(|(key, _)| allowlist.is_empty() || matches_any(allowlist, key))
+            .filter(|(key, _)| !matches_any(denylist, key))
+            .map(|(key, value)| {
+                let value = if SENSITIVE_ENV_RE.is_match(&key) {
+                    FILTERED_VALUE.to_string()
+                } else {
+                    value
+                };
+                (key, Value::String(value))
+            })
+            .collect(),
+    )
+}
+
+/// Returns the environ allowlist and denylist patterns.
+fn get_environ_patterns(matches: &ArgMatches<'_>) -> Result<(Vec<Pattern>, Vec<Pattern>), Error> {
+    let patterns = |name| -> Result<Vec<Pattern>, Error> {
+        Ok(matches
+            .values_of(name)
+            .unwrap_or_default()
+            .map(Pattern::new)
+            .collect::<Result<_, _>>()?)
+    };
+    Ok((
+        patterns("environ_allowlist")?,
+        patterns("environ_denylist")?,
+    ))
+}
+
+/// Collects environment variables as configured by the allowlist and
+/// denylist arguments.
+fn get_environ_from_matches(matches: &ArgMatches<'_>) -> Result<Value, Error> {
+    let (allowlist, denylist) = get_environ_patterns(matches)?;
+    let mut environ = get_environ(&allowlist, &denylist);
+    if let Value::Object(ref mut environ) = environ {
+        for key in matches
+            .values_of("disable_environ_for_keys")
+            .unwrap_or_default()
+        {
+            environ.remove(key.trim());
+        }
+    }
+    check_environ_secrets(&environ, matches.is_present("strict_secrets"))?;
+    Ok(environ)
+}
+
+/// Collects tags from environment variables starting with the prefix.  The
+/// prefix is removed from the tag key, which is lowercased.  Variables that
+/// look like they contain secrets are skipped.
+fn get_env_tags(
+    prefix: &str,
+    allowlist: &[Pattern],
+    denylist: &[Pattern],
+) -> Vec<(String, String)> {
+    let environ = match get_environ(allowlist, denylist) {
+        Value::Object(environ) => environ,
+        _ => return vec![],
+    };
+    environ
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let name = key.strip_prefix(prefix).filter(|name| !name.is_empty())?;
+            let value = value.as_str()?;
+            if SENSITIVE_ENV_RE.is_match(&key) || get_secret_kind(value).is_some() {
+                debug!("Not adding environment variable {} as tag", key);
+                return None;
+            }
+            Some((name.to_lowercase(), value.to_owned()))
+        })
+        .collect()
+}
+
+/// Returns the kind of secret the value looks like, if any.
+fn get_secret_kind(value: &str) -> Option<&'static str> {
+    if let Some((kind, _)) = SECRET_VALUE_PATTERNS
+        .iter()
+        .find(|(_, pattern)| pattern.is_match(value))
+    {
+        return Some(kind);
+    }
+
+    let is_token = value.len() >= MIN_SECRET_LENGTH
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+/=_-".contains(c));
+    if is_token && get_entropy(value) >= MIN_SECRET_ENTROPY {
+        Some("high-entropy token")
+    } else {
+        None
+    }
+}
+
+/// Computes the Shannon entropy of a string in bits per character.
+fn get_entropy(value: &str) -> f64 {
+    let mut counts = HashMap::new();
+    for c in value.chars() {
+        *counts.entry(c).or_insert(0usize) += 1;
+    }
+    let len = value.chars().count() as f64;
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Warns about environment variables with values that look like secrets, or
+/// fails in strict mode.
+fn check_environ_secrets(environ: &Value, strict: bool) -> Result<(), Error> {
+    let suspicious: Vec<_> = environ
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, value)| {
+            let kind = get_secret_kind(value.as_str()?)?;
+            Some(format!("{} ({})", key, kind))
+        })
+        .collect();
+
+    if suspicious.is_empty() {
+        return Ok(());
+    }
+    let message = format!(
+        "Environment variables look like they contain secrets: {}.  Exclude them with \
+         --environ-denylist or pass --no-environ.",
+        suspicious.join(", ")
+    );
+    if strict {
+        bail!(message);
+    }
+    warn!("{}", message);
+    Ok(())
+}
+
+/// Returns the command line arguments without credentials.  Values that look
+/// like secrets and values of pairs with sensitive keys are filtered.
+fn get_captured_args<I: IntoIterator<Item = String>>(args: I) -> Vec<String> {
+    let mut rv = vec![];
+    let mut skip_value = false;
+    for arg in args {
+        if skip_value {
+            skip_value = false;
+        } else if CREDENTIAL_ARGS.contains(&arg.as_str()) {
+            skip_value = true;
+        } else if CREDENTIAL_ARGS
+            .iter()
+            .any(|name| arg.starts_with(name) && arg[name.len()..].starts_with('='))
+        {
+            // dropped along with the value in the same argument
+        } else if get_secret_kind(&arg).is_some() {
+            rv.push(FILTERED_VALUE.to_string());
+        } else {
+            match ARG_PAIR_RE.captures(&arg) {
+                Some(caps) if SENSITIVE_ENV_RE.is_match(&caps["key"]) => {
+                    rv.push(format!("{}{}", &caps[0], FILTERED_VALUE));
+                }
+                _ => rv.push(arg),
+            }
+        }
+    }
+    rv
+}
+
+/// Parses a `CATEGORY=LEVEL` mapping for logfile breadcrumbs.
+fn parse_category_level(value: &str) -> Result<(String, Level), Error> {
+    let (category, level) = match value.rfind('=') {
+        Some(pos) => (&value[..pos], &value[pos + 1..]),
+        None => bail!(
+            "Invalid category level '{}', expected CATEGORY=LEVEL",
+            value
+        ),
+    };
+    let level = level
+        .parse()
+        .map_err(|_| failure::err_msg(format!("Unknown level '{}'", level)))?;
+    Ok((category.to_owned(), level))
+}
+
+/// Parses the IP address of the user.  Accepts IPv4 and IPv6 addresses, the
+/// latter optionally in brackets, and `{{auto}}` to let Sentry infer it.
+fn parse_ip_address(value: &str) -> Result<IpAddress, Error> {
+    let addr = value
+        .strip_prefix('[')
+        .and_then(|v| v.strip_suffix(']'))
+        .unwrap_or(value);
+    Ok(addr.parse::<IpAddress>().with_context(|_| {
+        format!(
+            "Invalid user ip_address '{}' (expected an IP address or {{{{auto}}}})",
+            value
+        )
+    })?)
+}
+
+/// Converts an extra value to a number or boolean if it looks like one.
+///
+/// Values wrapped in double quotes are unquoted and always kept as strings.
+fn infer_extra_value(value: &str) -> Value {
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        return Value::String(value[1..value.len() - 1].into());
+    }
+
+    match value {
+        "true" => return Value::Bool(true),
+        "false" => return Value::Bool(false),
+        _ => {}
+    }
+
+    if let Ok(number) = value.parse::<i64>() {
+        Value::from(number)
+    } else if let Ok(number) = value.parse::<u64>() {
+        Value::from(number)
+    } else if let Some(number) = value
+        .parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+    {
+        Value::Number(number)
+    } else {
+        Value::String(value.into())
+    }
+}
+
+/// Expands `${VAR}` references to environment variables in a value.
+///
+/// Undefined variables are an error unless `lenient` is set, in which case
+/// they expand to an empty string.
+fn expand_env_vars(value: &str, lenient: bool) -> Result<String, Error> {
+    let mut rv = String::new();
+    let mut last = 0;
+    for captures in ENV_VAR_RE.captures_iter(value) {
+        let reference = captures.get(0).unwrap();
+        let name = &captures[1];
+        let expanded = match env::var(name) {
+            Ok(expanded) => expanded,
+            Err(_) if lenient => String::new(),
+            Err(_) => bail!("Environment variable {} is not defined", name),
+        };
+        rv.push_str(&value[last..reference.start()]);
+        rv.push_str(&expanded);
+        last = reference.end();
+    }
+    rv.push_str(&value[last..]);
+    Ok(rv)
+}
+
+/// Parses tags from a JSON object.
+///
+/// Values other than strings are converted to their JSON representation,
+/// or rejected if `strict` is set.
+fn parse_tags_json(json: &str, strict: bool) -> Result<Vec<(String, String)>, Error> {
+    let tags: serde_json::Map<String, Value> =
+        serde_json::from_str(json).context("Invalid JSON object for --tags-json")?;
+    tags.into_iter()
+        .map(|(key, value)| match value {
+            Value::String(value) => Ok((key, value)),
+            value if strict => bail!("Tag '{}' is not a string: {}", key, value),
+            value => Ok((key, value.to_string())),
+        })
+        .collect()
+}
+
+/// Splits a `key<delimiter>value` pair passed on the command line.
+///
+/// The pair is split at the first delimiter that is not escaped with a
+/// backslash, so keys can contain the delimiter (eg: `http\:status:500`).
+fn split_pair<'a>(
+    pair: &'a str,
+    delimiter: char,
+    kind: &str,
+) -> Result<(Cow<'a, str>, &'a str), Error> {
+    let escaped = format!("\\{}", delimiter);
+    let mut offset = 0;
+    while let Some(idx) = pair[offset..].find(delimiter) {
+        let idx = offset + idx;
+        let value_start = idx + delimiter.len_utf8();
+        if pair[..idx].ends_with('\\') {
+            offset = value_start;
+            continue;
+        }
+
+        let key = &pair[..idx];
+        let key = if key.contains(&escaped) {
+            Cow::Owned(key.replace(&escaped, &delimiter.to_string()))
+        } else {
+            Cow::Borrowed(key)
+        };
+        return Ok((key, &pair[value_start..]));
+    }
+
+    bail!(
+        "missing {} value in '{}' (expected key{}value)",
+        kind,
+        pair,
+        delimiter
+    )
+}
+
+/// Builds the log entry from the message and its positional or named parameters.
+///
+/// Named `{name}` placeholders are converted into positional `%s` ones, as
+/// the protocol only supports positional parameters.
+fn get_logentry(message: &str, matches: &ArgMatches<'_>) -> Result<LogEntry, Error> {
+    let named = match matches.values_of("message_params") {
+        Some(named) => named,
+        None => {
+            return Ok(LogEntry {
+                message: message.to_string(),
+                params: matches
+                    .values_of("message_args")
+                    .map(|args| args.map(|x| x.into()).collect())
+                    .unwrap_or_default(),
+            })
+        }
+    };
+
+    let mut values = HashMap::new();
+    for pair in named {
+        let (name, value) = split_pair(pair, '=', "message parameter")?;
+        values.insert(name, value);
+    }
+
+    let mut params = vec![];
+    let mut template = String::new();
+    let mut last = 0;
+    for captures in MESSAGE_PARAM_RE.captures_iter(message) {
+        let placeholder = captures.get(0).unwrap();
+        let name = &captures[1];
+        let value = match values.get(name) {
+            Some(value) => value,
+            None => bail!("Missing value for message parameter '{}'", name),
+        };
+        template.push_str(&message[last..placeholder.start()]);
+        template.push_str("%s");
+        params.push(Value::String(value.to_string()));
+        last = placeholder.end();
+    }
+    template.push_str(&message[last..]);
+
+    Ok(LogEntry {
+        message: template,
+        params,
+    })
+}
+
+/// Reads `key<delimiter>value` pairs from a file, one per line.  Empty lines
+/// and lines starting with `#` are skipped.
+fn read_pairs_file(
+    path: &Path,
+    delimiter: char,
+    kind: &str,
+) -> Result<Vec<(String, String)>, Error> {
+    let contents = fs::read_to_string(path)
+        .with_context(|_| format!("Could not read {}s file {}", kind, path.display()))?;
+
+    let mut rv = vec![];
+    for (idx, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = split_pair(line, delimiter, kind).with_context(|_| {
+            format!("Invalid {} on line {} of {}", kind, idx + 1, path.display())
+        })?;
+        rv.push((key.into_owned(), value.to_string()));
+    }
+    Ok(rv)
+}
+
+/// Reads contexts from a JSON object that maps context names to objects.
+fn read_contexts_file(path: &Path) -> Result<Vec<(String, Context)>, Error> {
+    let contents = fs::read_to_string(path)
+        .with_context(|_| format!("Could not read contexts file {}", path.display()))?;
+    let contexts: serde_json::Map<String, Value> = serde_json::from_str(&contents)
+        .with_context(|_| format!("Invalid contexts file {}", path.display()))?;
+
+    let mut rv = vec![];
+    for (key, mut value) in contexts {
+        let object = match value.as_object_mut() {
+            Some(object) => object,
+            None => bail!(
+                "Invalid context '{}' in {}: expected an object",
+                key,
+                path.display()
+            ),
+        };
+
+        // like Sentry, default the type of the context to its name
+        let ty = object
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or(&key)
+            .to_owned();
+        let context = if KNOWN_CONTEXT_TYPES.contains(&ty.as_str()) {
+            object.insert("type".into(), Value::String(ty));
+            serde_json::from_value(value)
+                .with_context(|_| format!("Invalid context '{}' in {}", key, path.display()))?
+        } else {
+            object.remove("type");
+            Context::Other(std::mem::take(object).into_iter().collect())
+        };
+        rv.push((key, context));
+    }
+    Ok(rv)
+}
+
+/// Reads a stacktrace from a JSON array of frames or from lines of
+/// `file:line:function`.
+fn read_stacktrace(path: &Path) -> Result<Stacktrace, Error> {
+    let contents = fs::read_to_string(path)
+        .with_context(|_| format!("Could not read stacktrace file {}", path.display()))?;
+
+    let frames: Vec<Frame> = if contents.trim_start().starts_with('[') {
+        let frames: Vec<Frame> =
+            serde_json::from_str(&contents).context("Invalid stacktrace JSON")?;
+        for (idx, frame) in frames.iter().enumerate() {
+            if frame.function.is_none()
+                && frame.filename.is_none()
+                && frame.abs_path.is_none()
+                && frame.instruction_addr.is_none()
+            {
+                bail!(
+                    "Frame {} needs a function, filename, abs_path or instruction_addr",
+                    idx
+                );
+            }
+        }
+        frames
+    } else {
+        let mut frames = vec![];
+        for (idx, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let captures = match STACKTRACE_LINE_RE.captures(line) {
+                Some(captures) => captures,
+                None => bail!(
+                    "Invalid frame on line {} of {} (expected file:line:function)",
+                    idx + 1,
+                    path.display()
+                ),
+            };
+            frames.push(Frame {
+                filename: Some(captures["file"].to_string()),
+                lineno: Some(captures["line"].parse()?),
+                function: Some(captures["function"].to_string()),
+                ..Default::default()
+            });
+        }
+        frames
+    };
+
+    if frames.is_empty() {
+        bail!("Stacktrace file {} contains no frames", path.display());
+    }
+
+    Ok(Stacktrace {
+        frames,
+        ..Default::default()
+    })
+}
+
+/// Checks that the platform is known to Sentry, suggesting the closest known
+/// platform otherwise.
+fn validate_platform(platform: &str) -> Result<(), Error> {
+    if KNOWN_PLATFORMS.contains(&platform) {
+        return Ok(());
+    }
+
+    let closest = KNOWN_PLATFORMS
+        .iter()
+        .min_by_key(|known| strsim::levenshtein(known, platform))
+        .unwrap();
+    bail!(
+        "Unknown platform '{}', did you mean '{}'? (known platforms: {})",
+        platform,
+        closest,
+        KNOWN_PLATFORMS.join(", ")
+    );
+}
+
+/// Returns the uppercased request method, failing for unknown methods if
+/// `strict`.
+fn parse_request_method(method: &str, strict: bool) -> Result<String, Error> {
+    let method = method.to_uppercase();
+    if !KNOWN_HTTP_METHODS.contains(&method.as_str()) {
+        if strict {
+            bail!(
+                "Unknown request method '{}' (known methods: {})",
+                method,
+                KNOWN_HTTP_METHODS.join(", ")
+            );
+        }
+        warn!("Unknown request method '{}'", method);
+    }
+    Ok(method)
+}
+
+/// Parses a breadcrumb given as `level:category:message` or JSON object.
+fn parse_breadcrumb(value: &str) -> Result<Breadcrumb, Error> {
+    if value.trim_start().starts_with('{') {
+        return Ok(serde_json::from_str(value).context("Invalid breadcrumb JSON")?);
+    }
+
+    let mut parts = value.splitn(3, ':');
+    let level = parts.next().unwrap_or_default();
+    let (category, message) = match (parts.next(), parts.next()) {
+        (Some(category), Some(message)) => (category, message),
+        _ => bail!(
+            "Invalid breadcrumb '{}' (expected level:category:message)",
+            value
+        ),
+    };
+
+    Ok(Breadcrumb {
+        level: level
+            .parse()
+            .map_err(|_| failure::err_msg(format!("Unknown breadcrumb level '{}'", level)))?,
+        category: Some(category.to_string()).filter(|c| !c.is_empty()),
+        message: Some(message.to_string()),
+        ..Default::default()
+    })
+}
+
+/// Parses a thread given as `id:name[:crashed]`.
+fn parse_thread(value: &str) -> Result<Thread, Error> {
+    let mut parts = value.splitn(3, ':');
+    let (id, name) = match (parts.next(), parts.next()) {
+        (Some(id), Some(name)) if !id.is_empty() && !name.is_empty() => (id, name),
+        _ => bail!("Invalid thread '{}' (expected id:name[:crashed])", value),
+    };
+    let crashed = match parts.next() {
+        None => false,
+        Some("crashed") => true,
+        Some(flag) => bail!("Unknown flag '{}' for thread '{}'", flag, value),
+    };
+
+    Ok(Thread {
+        id: Some(match id.parse() {
+            Ok(id) => ThreadId::Int(id),
+            Err(_) => ThreadId::String(id.to_string()),
+        }),
+        name: Some(name.to_string()),
+        crashed,
+        ..Default::default()
+    })
+}
+
+/// Returns whether the file at the path is sent as an envelope.
+fn is_envelope_file(path: &Path, options: &BatchOptions) -> bool {
+    options.envelope || path.extension() == Some(OsStr::new("envelope"))
+}
+
+/// Returns whether the file at the path is a tar archive of event files.
+fn is_archive_file(path: &Path, options: &BatchOptions) -> bool {
+    let name = path.file_name().and_then(OsStr::to_str).unwrap_or("");
+    options.archive
+        || [".tar", ".tar.gz", ".tgz"]
+            .iter()
+            .any(|ext| name.ends_with(ext))
+}
+
+fn send_raw_event(event: Event<'static>, dsn: Dsn, options: &SendOptions) -> Result<Uuid, Error> {
+    log_event_summary(&event, &dsn);
+    let id = event.event_id;
+    // send the event as is, so that it matches the output of --dry-run
+    let envelope = Envelope::from(event);
+    let rv =
+        with_checked_sentry_client(dsn.clone(), options, |c| c.send_envelope(envelope)).map(|_| id);
+    log_send_result(id, &dsn, &rv);
+    rv
+}
+
+fn send_raw_event_with_attachments(
+    event: Event<'static>,
+    attachments: Vec<Attachment>,
+    dsn: Dsn,
+    options: &SendOptions,
+) -> Result<Uuid, Error> {
+    log_event_summary(&event, &dsn);
+    let id = event.event_id;
+    let mut envelope = Envelope::from(event);
+    for attachment in attachments {
+        envelope.add_item(EnvelopeItem::Attachment(attachment));
+    }
+    let rv =
+        with_checked_sentry_client(dsn.clone(), options, |c| c.send_envelope(envelope)).map(|_| id);
+    log_send_result(id, &dsn, &rv);
+    rv
+}
+
+/// Logs the key fields of an event before it is sent.  Only the host and
+/// project of the DSN are logged, never its key.
+fn log_event_summary(event: &Event<'_>, dsn: &Dsn) {
+    info!(
+        "Sending event {} to {} (project {}): level {}, release {}, environment {}, {} tag(s)",
+        event.event_id,
+        dsn.host(),
+        dsn.project_id(),
+        event.level,
+        event.release.as_deref().unwrap_or("-"),
+        event.environment.as_deref().unwrap_or("-"),
+        event.tags.len(),
+    );
+}
+
+/// Logs whether an event was delivered.
+fn log_send_result(id: Uuid, dsn: &Dsn, result: &Result<Uuid, Error>) {
+    match result {
+        Ok(_) => info!("Event {} delivered to {}", id, dsn.host()),
+        Err(err) => info!(
+            "Event {} not delivered to {}: {}",
+            id,
+            dsn.host(),
+            err.iter_chain().join(": ")
+        ),
+    }
+}
+
+/// Sends the same event to every DSN, continuing after failures.  Only fails
+/// if the event could not be sent to any of the DSNs.
+fn send_to_all_dsns(
+    event: Event<'static>,
+    attachments: Vec<Attachment>,
+    dsns: Vec<Dsn>,
+    options: &SendOptions,
+    output: OutputMode,
+) -> Result<(), Error> {
+    let total = dsns.len();
+    let mut failed = vec![];
+    for dsn in dsns {
+        let host = dsn.host().to_owned();
+        let result = if attachments.is_empty() {
+            send_raw_event(event.clone(), dsn, options)
+        } else {
+            send_raw_event_with_attachments(event.clone(), attachments.clone(), dsn, options)
+        };
+        match result {
+            Ok(id) => output.print_dispatched_to(&host, id),
+            Err(err) => {
+                eprintln!(
+                    "{} {}: {}",
+                    style("error:").red(),
+                    host,
+                    err.iter_chain().join(": ")
+                );
+                failed.push(err);
+            }
+        }
+    }
+
+    if failed.len() == total {
+        let err = failed.swap_remove(0);
+        return Err(err
+            .context(format!("Failed to send event to all {} DSN(s)", total))
+            .into());
+    }
+    Ok(())
+}
+
+/// Reads an event template to merge the command line arguments into.  The
+/// template always gets a new event ID.
+fn read_template(path: &Path) -> Result<Event<'static>, Error> {
+    let contents = read_event_file(path)
+        .with_context(|_| format!("Could not read template {}", path.display()))?;
+    let mut event: Event<'static> = serde_json::from_slice(&contents)
+        .with_context(|_| format!("Invalid event template {}", path.display()))?;
+    event.event_id = Uuid::new_v4();
+    Ok(event)
+}
+
+/// Truncates the message to at most `limit` bytes, including a suffix that
+/// marks it as truncated.  Returns whether the message was truncated.
+fn truncate_message(message: &mut String, limit: usize) -> bool {
+    if message.len() <= limit {
+        return false;
+    }
+    let mut end = limit.saturating_sub(TRUNCATED_SUFFIX.len());
+    while !message.is_char_boundary(end) {
+        end -= 1;
+    }
+    message.truncate(end);
+    message.push_str(TRUNCATED_SUFFIX);
+    true
+}
+
+/// Truncates a tag value to the length accepted by Sentry.  Returns whether
+/// the value was truncated.
+fn truncate_tag_value(value: &mut String) -> bool {
+    match value.char_indices().nth(MAX_TAG_VALUE_LENGTH) {
+        Some((end, _)) => {
+            value.truncate(end);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Reads the event message from a file or stdin (`-`).  A single trailing
+/// newline is removed.
+fn read_message_file(path: &str) -> Result<String, Error> {
+    let mut message = if path == "-" {
+        let mut message = String::new();
+        io::stdin()
+            .read_to_string(&mut message)
+            .context("Could not read message from stdin")?;
+        message
+    } else {
+        fs::read_to_string(path)
+            .with_context(|_| format!("Could not read message file {}", path))?
+    };
+    if message.ends_with('\n') {
+        message.pop();
+        if message.ends_with('\r') {
+            message.pop();
+        }
+    }
+    Ok(message)
+}
+
+/// Parses a `KEY=VALUE` line of an env file.  Returns `None` for blank
+/// lines and comments.
+///
+/// Values may be wrapped in double quotes, which support `\n`, `\"` and
+/// `\\` escapes, or single quotes, which are taken literally.  Unquoted
+/// values end at a ` #` comment.  Errors never contain the value, as it
+/// might be a secret.
+fn parse_env_line(line: &str) -> Result<Option<(String, String)>, Error> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+    let line = line.strip_prefix("export ").unwrap_or(line);
+    let (key, value) = match line.find('=') {
+        Some(idx) => (line[..idx].trim(), line[idx + 1..].trim()),
+        None => bail!("expected KEY=VALUE"),
+    };
+    if !ENV_KEY_RE.is_match(key) {
+        bail!("invalid variable name");
+    }
+
+    let value = if let Some(quoted) = value.strip_prefix('"') {
+        let mut rv = String::new();
+        let mut chars = quoted.chars();
+        loop {
+            match chars.next() {
+                Some('"') if chars.as_str().trim().is_empty() => break,
+                Some('"') => bail!("unexpected data after the closing quote"),
+                Some('\\') => match chars.next() {
+                    Some('n') => rv.push('\n'),
+                    Some(c @ '"') | Some(c @ '\\') => rv.push(c),
+                    Some(c) => {
+                        rv.push('\\');
+                        rv.push(c);
+                    }
+                    None => bail!("unterminated quote"),
+                },
+                Some(c) => rv.push(c),
+                None => bail!("unterminated quote"),
+            }
+        }
+        rv
+    } else if let Some(quoted) = value.strip_prefix('\'') {
+        match quoted.find('\'') {
+            Some(end) if quoted[end + 1..].trim().is_empty() => quoted[..end].to_string(),
+            Some(_) => bail!("unexpected data after the closing quote"),
+            None => bail!("unterminated quote"),
+        }
+    } else {
+        match value.find(" #") {
+            Some(idx) => value[..idx].trim_end().to_string(),
+            None => value.to_string(),
+        }
+    };
+
+    Ok(Some((key.to_string(), value)))
+}
+
+/// Loads the variables of an env file into the environment.  Variables that
+/// are already set are kept unless `overwrite`.
+fn load_env_file(path: &Path, overwrite: bool) -> Result<(), Error> {
+    let contents = fs::read_to_string(path)
+        .with_context(|_| format!("Could not read env file {}", path.display()))?;
+
+    let mut vars = vec![];
+    for (idx, line) in contents.lines().enumerate() {
+        let var = parse_env_line(line)
+            .with_context(|_| format!("Invalid line {} in env file {}", idx + 1, path.display()))?;
+        vars.extend(var);
+    }
+
+    // only touch the environment once the whole file is valid
+    for (key, value) in vars {
+        if overwrite || env::var_os(&key).is_none() {
+            env::set_var(key, value);
+        }
+    }
+    Ok(())
+}
+
+/// Reads the environment name from a file.  Fails if the file cannot be read
+/// or is empty.
+fn read_environment_file(path: &Path) -> Result<String, Error> {
+    let contents = fs::read_to_string(path)
+        .with_context(|_| format!("Could not read environment file {}", path.display()))?;
+    let environment = contents.trim();
+    if environment.is_empty() {
+        bail!("Environment file {} is empty", path.display());
+    }
+    Ok(validate_environment_name(environment)
+        .with_context(|_| format!("Invalid environment in {}", path.display()))?
+        .to_owned())
+}
+
+/// Returns the release from `SENTRY_RELEASE` like the Sentry SDKs, unless
+/// default tags or release detection are disabled.
+fn get_release_from_env(matches: &ArgMatches<'_>) -> Option<String> {
+    if matches.is_present("no_default_tags") || matches.is_present("no_release_detection") {
+        return None;
+    }
+    env::var("SENTRY_RELEASE")
+        .ok()
+        .map(|release| release.trim().to_owned())
+        .filter(|release| !release.is_empty())
+}
+
+/// Returns the environment from `SENTRY_ENVIRONMENT` like the Sentry SDKs,
+/// unless default tags or environment detection are disabled.
+fn get_environment_from_env(matches: &ArgMatches<'_>) -> Option<String> {
+    if matches.is_present("no_default_tags") || matches.is_present("no_detect_environment") {
+        return None;
+    }
+    let environment = env::var("SENTRY_ENVIRONMENT").ok()?;
+    match validate_environment_name(&environment) {
+        Ok(environment) => Some(environment.to_owned()),
+        Err(err) => {
+            warn!("Ignoring SENTRY_ENVIRONMENT: {}", err);
+            None
+        }
+    }
+}
+
+/// Reads a file as event attachment.  Returns `None` if the file exceeds
+/// the maximum attachment size.
+fn read_attachment(path: &Path) -> Result<Option<Attachment>, Error> {
+    let size = fs::metadata(path)
+        .with_context(|_| format!("Could not read attachment {}", path.display()))?
+        .len();
+    if size > MAX_ATTACHMENT_SIZE {
+        warn!(
+            "Skipping attachment since it exceeds {}: {} ({})",
+            HumanBytes(MAX_ATTACHMENT_SIZE),
+            path.display(),
+            HumanBytes(size),
+        );
+        return Ok(None);
+    }
+
+    Ok(Some(Attachment {
+        buffer: fs::read(path)
+            .with_context(|_| format!("Could not read attachment {}", path.display()))?,
+        filename: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        ty: None,
+    }))
+}
+
+/// Parses an attachment given as `name=content`.  Returns `None` if the
+/// content exceeds the maximum attachment size.
+fn parse_attachment_string(value: &str) -> Result<Option<Attachment>, Error> {
+    let (name, content) = match value.find('=') {
+        Some(idx) if idx > 0 => (&value[..idx], &value[idx + 1..]),
+        _ => bail!(
+            "Invalid attachment '{}' (expected name=content)",
+            value.chars().take(32).collect::<String>()
+        ),
+    };
+    if content.len() as u64 > MAX_ATTACHMENT_SIZE {
+        warn!(
+            "Skipping attachment since it exceeds {}: {} ({})",
+            HumanBytes(MAX_ATTACHMENT_SIZE),
+            name,
+            HumanBytes(content.len() as u64),
+        );
+        return Ok(None);
+    }
+
+    Ok(Some(Attachment {
+        buffer: content.as_bytes().to_vec(),
+        filename: name.to_string(),
+        ty: None,
+    }))
+}
+
+/// Reads a minidump to send along with the event.
+fn read_minidump(path: &Path) -> Result<Attachment, Error> {
+    let buffer =
+        fs::read(path).with_context(|_| format!("Could not read minidump {}", path.display()))?;
+    if buffer.is_empty() {
+        bail!("Minidump {} is empty", path.display());
+    }
+    if !buffer.starts_with(MINIDUMP_MAGIC) {
+        warn!("File {} does not look like a minidump", path.display());
+    }
+
+    Ok(Attachment {
+        buffer,
+        filename: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        ty: Some(AttachmentType::Minidump),
+    })
+}
+
+/// Batch-level values merged onto every event read from files.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct EventOverrides {
+    tags: HashMap<String, String>,
+    extra: serde_json::Map<String, Value>,
+    environment: Option<String>,
+}
+
+impl EventOverrides {
+    /// Merges the overrides onto an event, replacing existing values.
+    fn apply(&self, event: &mut Event<'_>) {
+        event
+            .tags
+            .extend(self.tags.iter().map(|(k, v)| (k.clone(), v.clone())));
+        event
+            .extra
+            .extend(self.extra.iter().map(|(k, v)| (k.clone(), v.clone())));
+        if let Some(ref environment) = self.environment {
+            event.environment = Some(environment.clone().into());
+        }
+    }
+}
+
+/// Reads the overrides merged onto events read from files.
+fn read_overrides(path: &Path) -> Result<EventOverrides, Error> {
+    let contents =
+        fs::read(path).with_context(|_| format!("Could not read overrides {}", path.display()))?;
+    let mut overrides: EventOverrides = serde_json::from_slice(&contents)
+        .with_context(|_| format!("Invalid overrides {}", path.display()))?;
+    if let Some(environment) = overrides.environment.take() {
+        let environment = validate_environment_name(&environment)
+            .with_context(|_| format!("Invalid overrides {}", path.display()))?;
+        overrides.environment = Some(environment.to_owned());
+    }
+    Ok(overrides)
+}
+
+/// Options for sending events read from files.
+struct BatchOptions {
+    level: Option<Level>,
+    min_level: Option<Level>,
+    ndjson: bool,
+    envelope: bool,
+    archive: bool,
+    format: Option<FileFormat>,
+    sample_rate: f64,
+    dry_run: bool,
+    fail_fast: bool,
+    strict_limits: bool,
+    schema: Option<Value>,
+    overrides: Option<EventOverrides>,
+    environ: Option<Value>,
+    release: Option<String>,
+    environment: Option<String>,
+    send: SendOptions,
+}
+
+/// What happened to a single event read from a file.
+enum BatchEvent {
+    Sent(Uuid),
+    Failed(Error),
+    DryRun(Box<Event<'static>>),
+    DryRunEnvelope(Uuid),
+    Skipped,
+    BelowMinLevel,
+}
+
+/// The events of a file or an error if it could not be processed.
+type FileResult = (PathBuf, Result<Vec<BatchEvent>, Error>);
+
+/// Counts the files and events processed when sending event files.
+#[derive(Debug, Default)]
+struct BatchSummary {
+    dry_run: bool,
+    files: usize,
+    files_failed: usize,
+    files_skipped: usize,
+    matched: usize,
+    sent: usize,
+    skipped: usize,
+    below_min_level: usize,
+    rate_limit: Option<RateLimitSimulation>,
+}
+
+/// Counts the events per second to estimate the effect of a rate limit.
+#[derive(Debug)]
+struct RateLimitSimulation {
+    limit: usize,
+    per_second: HashMap<i64, usize>,
+}
+
+impl RateLimitSimulation {
+    fn new(limit: usize) -> RateLimitSimulation {
+        RateLimitSimulation {
+            limit,
+            per_second: HashMap::new(),
+        }
+    }
+
+    fn add(&mut self, timestamp: DateTime<Utc>) {
+        *self.per_second.entry(timestamp.timestamp()).or_insert(0) += 1;
+    }
+
+    /// Returns the number of events over the limit of their second.
+    fn dropped(&self) -> usize {
+        self.per_second
+            .values()
+            .map(|count| count.saturating_sub(self.limit))
+            .sum()
+    }
 }
 
-fn send_raw_event(event: Event<'static>, dsn: Dsn) -> Uuid {
-    with_sentry_client(dsn, |c| c.capture_event(event, None))
+/// Reads all events from a file and sends them, unless dropped by sampling.
+fn process_event_file(
+    path: &Path,
+    dsn: &Dsn,
+    options: &BatchOptions,
+) -> Result<Vec<BatchEvent>, Error> {
+    let mut rng = rand::thread_rng();
+    let mut rv = vec![];
+
+    if is_envelope_file(path, options) {
+        let envelope = read_event_file(path)?;
+        let ids = get_envelope_event_ids(&envelope)
+            .with_context(|_| format!("Invalid envelope {}", path.display()))?;
+        if options.sample_rate < 1.0 && rng.gen::<f64>() >= options.sample_rate {
+            debug!("Skipping envelope {}", path.display());
+            rv.extend(ids.iter().map(|_| BatchEvent::Skipped));
+        } else if options.dry_run {
+            rv.extend(ids.into_iter().map(BatchEvent::DryRunEnvelope));
+        } else {
+            send_raw_envelope(dsn, envelope, &options.send)?;
+            rv.extend(ids.into_iter().map(BatchEvent::Sent));
+        }
+        return Ok(rv);
+    }
+
+    process_events(
+        path,
+        read_events(path, options.ndjson, options.format)?,
+        dsn,
+        options,
+    )
+}
+
+/// Reads the .json files in a tar archive and sends their events.  Returns
+/// the results for every file separately.
+fn process_archive_file(
+    path: &Path,
+    dsn: &Dsn,
+    options: &BatchOptions,
+) -> Result<Vec<FileResult>, Error> {
+    let archive = read_event_file(path)?;
+    let entries = tar::read_entries(&archive)
+        .with_context(|_| format!("Invalid archive {}", path.display()))?;
+
+    let mut rv = vec![];
+    for entry in entries {
+        if !entry.name.ends_with(".json") {
+            debug!("Skipping {} in archive {}", entry.name, path.display());
+            continue;
+        }
+        let entry_path = path.join(&entry.name);
+        let result = str::from_utf8(entry.data)
+            .map_err(Error::from)
+            .and_then(|contents| parse_events(contents, options.ndjson))
+            .and_then(|events| process_events(&entry_path, events, dsn, options));
+        rv.push((entry_path, result));
+    }
+    Ok(rv)
+}
+
+/// Applies the batch options to events read from a file and sends them,
+/// unless dropped by sampling.  Events that fail to send are recorded and the
+/// remaining events are still sent, unless failing fast.
+fn process_events(
+    path: &Path,
+    events: Vec<Event<'static>>,
+    dsn: &Dsn,
+    options: &BatchOptions,
+) -> Result<Vec<BatchEvent>, Error> {
+    let mut rng = rand::thread_rng();
+    let mut rv = vec![];
+
+    for mut event in events {
+        if let Some(level) = options.level {
+            event.level = level;
+        }
+        if let Some(ref overrides) = options.overrides {
+            overrides.apply(&mut event);
+        }
+        if let Some(ref environ) = options.environ {
+            event
+                .extra
+                .entry("environ".into())
+                .or_insert_with(|| environ.clone());
+        }
+        if event.release.is_none() {
+            event.release = options.release.clone().map(Cow::from);
+        }
+        if event.environment.is_none() {
+            event.environment = options.environment.clone().map(Cow::from);
+        }
+        check_event_limits(&event, options.strict_limits)?;
+        if let Some(ref schema) = options.schema {
+            check_event_schema(&event, schema)?;
+        }
+        if matches!(options.min_level, Some(min) if event.level < min) {
+            debug!(
+                "Skipping event {} from file {} with level {}",
+                event.event_id,
+                path.display(),
+                event.level
+            );
+            rv.push(BatchEvent::BelowMinLevel);
+        } else if options.sample_rate < 1.0 && rng.gen::<f64>() >= options.sample_rate {
+            debug!(
+                "Skipping event {} from file {}",
+                event.event_id,
+                path.display()
+            );
+            rv.push(BatchEvent::Skipped);
+        } else if options.dry_run {
+            rv.push(BatchEvent::DryRun(Box::new(event)));
+        } else {
+            match send_raw_event(event, dsn.clone(), &options.send) {
+                Ok(id) => rv.push(BatchEvent::Sent(id)),
+                Err(err) => {
+                    rv.push(BatchEvent::Failed(err));
+                    if options.fail_fast {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(rv)
+}
+
+/// Controls how the results of the command are printed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum OutputMode {
+    Text,
+    Quiet,
+    Json,
+}
+
+impl OutputMode {
+    fn from_matches(matches: &ArgMatches<'_>) -> OutputMode {
+        if matches.is_present("quiet") {
+            OutputMode::Quiet
+        } else if matches.value_of("output") == Some("json") {
+            OutputMode::Json
+        } else {
+            OutputMode::Text
+        }
+    }
+
+    /// Prints the ID of a dispatched event.
+    fn print_dispatched(self, path: Option<&Path>, id: Uuid) {
+        match self {
+            OutputMode::Text => match path {
+                Some(path) => println!("Event from file {} dispatched: {}", path.display(), id),
+                None => println!("Event dispatched: {}", id),
+            },
+            OutputMode::Quiet => println!("{}", id),
+            OutputMode::Json => println!("{}", json_output(path, id, None)),
+        }
+    }
+
+    /// Prints the ID of an event dispatched to one of several DSNs.
+    fn print_dispatched_to(self, host: &str, id: Uuid) {
+        match self {
+            OutputMode::Text => println!("Event dispatched to {}: {}", host, id),
+            OutputMode::Quiet => println!("{} {}", host, id),
+            OutputMode::Json => {
+                let mut output = json_output(None, id, None);
+                output["dsn_host"] = Value::String(host.to_owned());
+                println!("{}", output);
+            }
+        }
+    }
+
+    /// Prints the ID of an event in an envelope in dry-run mode.
+    fn print_envelope(self, path: &Path, id: Uuid) {
+        match self {
+            OutputMode::Text => println!("Envelope {} contains event: {}", path.display(), id),
+            OutputMode::Quiet => println!("{}", id),
+            OutputMode::Json => println!("{}", json_output(Some(path), id, None)),
+        }
+    }
+
+    /// Prints the summary of sending event files.
+    fn print_summary(self, summary: &BatchSummary, elapsed: Duration) {
+        match self {
+            OutputMode::Text => {
+                let skipped = summary.skipped + summary.below_min_level;
+                println!(
+                    "{} {} event(s) from {} of {} file(s) in {:.2}s: {} file(s) failed, \
+                     {} file(s) and {} event(s) skipped{}",
+                    if summary.dry_run {
+                        "Would send"
+                    } else {
+                        "Sent"
+                    },
+                    summary.sent,
+                    summary.files - summary.files_failed - summary.files_skipped,
+                    summary.files,
+                    elapsed.as_secs_f64(),
+                    summary.files_failed,
+                    summary.files_skipped,
+                    skipped,
+                    if skipped > 0 {
+                        format!(
+                            " ({} by sampling, {} below the minimum level)",
+                            summary.skipped, summary.below_min_level
+                        )
+                    } else {
+                        String::new()
+                    }
+                );
+                if let Some(ref rate_limit) = summary.rate_limit {
+                    let dropped = rate_limit.dropped();
+                    println!(
+                        "With a rate limit of {} event(s) per second: {} accepted, {} dropped",
+                        rate_limit.limit,
+                        summary.sent - dropped,
+                        dropped
+                    );
+                }
+            }
+            OutputMode::Quiet => {}
+            OutputMode::Json => {
+                let mut output = serde_json::Map::new();
+                output.insert("files".into(), summary.files.into());
+                output.insert("files_failed".into(), summary.files_failed.into());
+                output.insert("files_skipped".into(), summary.files_skipped.into());
+                output.insert("events".into(), summary.matched.into());
+                output.insert("events_sent".into(), summary.sent.into());
+                output.insert(
+                    "events_skipped".into(),
+                    (summary.skipped + summary.below_min_level).into(),
+                );
+                output.insert("events_sampled_out".into(), summary.skipped.into());
+                output.insert(
+                    "events_below_min_level".into(),
+                    summary.below_min_level.into(),
+                );
+                output.insert("elapsed_ms".into(), (elapsed.as_millis() as u64).into());
+                if let Some(ref rate_limit) = summary.rate_limit {
+                    let dropped = rate_limit.dropped();
+                    output.insert(
+                        "rate_limit".into(),
+                        serde_json::json!({
+                            "limit": rate_limit.limit,
+                            "accepted": summary.sent - dropped,
+                            "dropped": dropped,
+                        }),
+                    );
+                }
+                println!("{}", serde_json::json!({ "summary": output }));
+            }
+        }
+    }
+
+    /// Prints an event instead of sending it in dry-run mode.
+    fn print_event(self, path: Option<&Path>, event: &Event<'_>) -> Result<(), Error> {
+        let mut payload = serde_json::to_value(event)?;
+        normalize_payload(&mut payload, false);
+        match self {
+            OutputMode::Text => println!("{}", serde_json::to_string_pretty(&payload)?),
+            OutputMode::Quiet => println!("{}", event.event_id),
+            OutputMode::Json => println!("{}", json_output(path, event.event_id, Some(payload))),
+        }
+        Ok(())
+    }
+}
+
+fn json_output(path: Option<&Path>, id: Uuid, event: Option<Value>) -> Value {
+    let mut rv = serde_json::Map::new();
+    rv.insert("id".into(), Value::String(id.to_string()));
+    if let Some(path) = path {
+        rv.insert("path".into(), Value::String(path.display().to_string()));
+    }
+    if let Some(event) = event {
+        rv.insert("event".into(), event);
+    }
+    Value::Object(rv)
 }
 
 pub fn execute(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    let err = match execute_send(matches) {
+        Ok(()) => return Ok(()),
+        Err(err) => err,
+    };
+
+    let exit_code = match get_send_error(&err) {
+        Some(_) if matches.is_present("ignore_send_errors") => {
+            eprintln!(
+                "{} {}",
+                style("warning:").yellow(),
+                err.iter_chain().join(": ")
+            );
+            return Ok(());
+        }
+        Some(send_error) => send_error.kind.exit_code(),
+        None if is_dsn_error(&err) => EXIT_AUTH,
+        None if err.downcast_ref::<DeadlineError>().is_some() => EXIT_DEADLINE,
+        // everything else fails before an event is sent
+        None => EXIT_INVALID_INPUT,
+    };
+
+    print_error(&err);
+    Err(QuietExit(exit_code).into())
+}
+
+fn get_confirm_threshold(matches: &ArgMatches<'_>) -> Result<usize, Error> {
+    Ok(matches
+        .value_of("confirm_threshold")
+        .map(str::parse)
+        .transpose()?
+        .unwrap_or(DEFAULT_CONFIRM_THRESHOLD))
+}
+
+/// Asks whether a large send should go ahead, unless --yes was passed.
+///
+/// Without a terminal to ask on, the send is denied.
+fn confirm_large_send(matches: &ArgMatches<'_>, message: &str) -> Result<bool, Error> {
+    if matches.is_present("yes") {
+        return Ok(true);
+    }
+    if !io::stdin().is_terminal() {
+        eprintln!(
+            "{} {} Pass --yes to confirm without a terminal.",
+            style("warning:").yellow(),
+            message
+        );
+        return Ok(false);
+    }
+    Ok(prompt_to_continue(message)?)
+}
+
+/// Error returned when the DSN is missing or invalid.
+#[derive(Debug, Fail)]
+#[fail(display = "No valid DSN configured")]
+struct DsnError;
+
+/// Error returned when --deadline expired before all event files were sent.
+#[derive(Debug, Fail)]
+#[fail(
+    display = "Deadline exceeded, skipped {} of {} event file(s)",
+    skipped, total
+)]
+struct DeadlineError {
+    skipped: usize,
+    total: usize,
+}
+
+fn is_dsn_error(err: &Error) -> bool {
+    err.iter_chain()
+        .any(|cause| cause.downcast_ref::<failure::Context<DsnError>>().is_some())
+}
+
+fn execute_send(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    if let Some(path) = matches.value_of("env_file") {
+        load_env_file(Path::new(path), matches.is_present("env_file_override"))?;
+    }
+    if matches.is_present("verbose") && max_level() < log::LevelFilter::Info {
+        set_max_level(log::LevelFilter::Info);
+    }
     let config = Config::current();
-    let dsn = config.get_dsn()?;
+    let dsn = config.get_dsn().context(DsnError)?;
+    let dry_run = matches.is_present("dry_run");
+    let output = OutputMode::from_matches(matches);
+    let mut send_options = SendOptions::default();
+    if let Some(retries) = matches.value_of("retries") {
+        send_options.retries = retries.parse()?;
+    }
+    if let Some(delay) = matches.value_of("retry_delay") {
+        send_options.retry_delay = Duration::from_millis(delay.parse()?);
+    }
+    if let Some(timeout) = matches.value_of("timeout") {
+        send_options.timeout = Duration::from_secs(timeout.parse()?);
+    }
+    if let Some(timeout) = matches.value_of("flush_timeout") {
+        send_options.flush_timeout = Some(Duration::from_secs(timeout.parse()?));
+    }
+    send_options.compress = matches.is_present("compress");
+    if let Some(relay_url) = matches.value_of("relay_url") {
+        send_options.relay_url = Some(relay_url.parse()?);
+    }
+
+    let schema = matches
+        .value_of("schema")
+        .map(|path| read_schema(Path::new(path)))
+        .transpose()?;
 
     if let Some(path) = matches.value_of("path") {
-        let collected_paths: Vec<PathBuf> = glob_with(path, MatchOptions::new())
+        let match_options = MatchOptions {
+            case_sensitive: !matches.is_present("glob_case_insensitive"),
+            ..MatchOptions::new()
+        };
+        let pattern = get_path_pattern(path, matches.is_present("recursive"));
+        let collected_paths: Vec<PathBuf> = glob_with(&pattern, match_options)
             .unwrap()
             .flatten()
             .collect();
 
         if collected_paths.is_empty() {
-            warn!("Did not match any .json files for pattern: {}", path);
+            warn!("Did not match any .json files for pattern: {}", pattern);
             return Ok(());
         }
 
-        for path in collected_paths {
-            let p = path.as_path();
-            let file = File::open(p)?;
-            let reader = BufReader::new(file);
-            let event: Event = serde_json::from_reader(reader)?;
-            let id = send_raw_event(event, dsn.clone());
-            println!("Event from file {} dispatched: {}", p.display(), id);
+        let threshold = get_confirm_threshold(matches)?;
+        if collected_paths.len() > threshold
+            && !confirm_large_send(
+                matches,
+                &format!("Send events from {} files?", collected_paths.len()),
+            )?
+        {
+            bail!(
+                "Refusing to send events from more than {} files without confirmation",
+                threshold
+            );
+        }
+
+        let options = BatchOptions {
+            level: matches.value_of("level").and_then(|l| l.parse().ok()),
+            min_level: matches.value_of("min_level").map(str::parse).transpose()?,
+            ndjson: matches.is_present("ndjson"),
+            envelope: matches.is_present("envelope"),
+            archive: matches.is_present("archive"),
+            format: FileFormat::from_matches(matches),
+            sample_rate: matches
+                .value_of("sample_rate")
+                .map(str::parse)
+                .transpose()?
+                .unwrap_or(1.0),
+            dry_run,
+            fail_fast: matches.is_present("fail_fast"),
+            strict_limits: matches.is_present("strict_limits"),
+            schema: schema.clone(),
+            overrides: matches
+                .value_of("overrides")
+                .map(|path| read_overrides(Path::new(path)))
+                .transpose()?,
+            environ: if matches.is_present("add_environ") {
+                Some(get_environ_from_matches(matches)?)
+            } else {
+                None
+            },
+            release: get_release_from_env(matches),
+            environment: get_environment_from_env(matches),
+            send: send_options,
+        };
+        let concurrency = matches
+            .value_of("concurrency")
+            .map(str::parse)
+            .transpose()?
+            .unwrap_or(1);
+
+        let fail_fast = options.fail_fast;
+        let aborted = AtomicBool::new(false);
+        let deadline = matches
+            .value_of("deadline")
+            .map(|secs| {
+                secs.parse()
+                    .map(|secs| Instant::now() + Duration::from_secs(secs))
+            })
+            .transpose()?;
+        let deadline_skipped = AtomicUsize::new(0);
+
+        let show_progress =
+            matches.is_present("progress") && output == OutputMode::Text && user_attended();
+        let progress = if show_progress {
+            let progress = ProgressBar::new(collected_paths.len() as u64);
+            progress.set_style(ProgressStyle::default_bar().template(
+                "{prefix:.dim} Sending events... {msg:.dim}\
+                 \n{wide_bar}  {pos}/{len}",
+            ));
+            progress.set_prefix(">");
+            Some(progress)
+        } else {
+            None
+        };
+        let files_failed = AtomicUsize::new(0);
+        let started = Instant::now();
+
+        let pool = ThreadPoolBuilder::new().num_threads(concurrency).build()?;
+        let results: Vec<_> = pool.install(|| {
+            collected_paths
+                .into_par_iter()
+                .flat_map(|path| {
+                    if aborted.load(Ordering::Relaxed) {
+                        return vec![(path, None)];
+                    }
+                    if matches!(deadline, Some(deadline) if Instant::now() >= deadline) {
+                        deadline_skipped.fetch_add(1, Ordering::Relaxed);
+                        return vec![(path, None)];
+                    }
+                    // archives report every file in them separately
+                    let results = if is_archive_file(&path, &options) {
+                        match process_archive_file(&path, &dsn, &options) {
+                            Ok(results) => results,
+                            Err(err) => vec![(path, Err(err))],
+                        }
+                    } else {
+                        let result = process_event_file(&path, &dsn, &options);
+                        vec![(path, result)]
+                    };
+                    for (_, result) in &results {
+                        let file_failed = match result {
+                            Ok(events) => events
+                                .iter()
+                                .any(|event| matches!(event, BatchEvent::Failed(_))),
+                            Err(_) => true,
+                        };
+                        if file_failed {
+                            files_failed.fetch_add(1, Ordering::Relaxed);
+                            if fail_fast {
+                                aborted.store(true, Ordering::Relaxed);
+                            }
+                        }
+                    }
+                    if let Some(ref progress) = progress {
+                        let failed = files_failed.load(Ordering::Relaxed);
+                        progress.inc(1);
+                        progress.set_message(&format!("{} failed", failed));
+                    }
+                    results
+                        .into_iter()
+                        .map(|(path, result)| (path, Some(result)))
+                        .collect::<Vec<_>>()
+                })
+                .collect()
+        });
+        if let Some(progress) = progress {
+            progress.finish_and_clear();
+        }
+
+        let total = results.len();
+        let verbose = matches.is_present("verbose");
+        // large runs only print the summary unless asked for every file
+        let print_files = output != OutputMode::Text || ((total == 1 || verbose) && !show_progress);
+        let mut summary = BatchSummary {
+            dry_run,
+            files: total,
+            rate_limit: matches
+                .value_of("simulate_rate_limit")
+                .map(str::parse)
+                .transpose()?
+                .map(RateLimitSimulation::new),
+            ..Default::default()
+        };
+        let mut failed = vec![];
+        for (path, result) in results {
+            let events = match result {
+                Some(Ok(events)) => events,
+                Some(Err(err)) if fail_fast => {
+                    return Err(err
+                        .context(format!("Failed to process {}", path.display()))
+                        .into());
+                }
+                Some(Err(err)) => {
+                    failed.push((path, err));
+                    continue;
+                }
+                // skipped after another file failed with --fail-fast or the deadline
+                None => {
+                    summary.files_skipped += 1;
+                    continue;
+                }
+            };
+
+            for event in events {
+                summary.matched += 1;
+                match event {
+                    BatchEvent::Sent(id) => {
+                        summary.sent += 1;
+                        if print_files {
+                            output.print_dispatched(Some(&path), id);
+                        }
+                    }
+                    BatchEvent::DryRun(event) => {
+                        summary.sent += 1;
+                        if let Some(ref mut rate_limit) = summary.rate_limit {
+                            rate_limit.add(event.timestamp);
+                        }
+                        output.print_event(Some(&path), &event)?;
+                    }
+                    BatchEvent::DryRunEnvelope(id) => {
+                        summary.sent += 1;
+                        // envelopes are not parsed, assume they are sent right away
+                        if let Some(ref mut rate_limit) = summary.rate_limit {
+                            rate_limit.add(Utc::now());
+                        }
+                        output.print_envelope(&path, id);
+                    }
+                    BatchEvent::Failed(err) if fail_fast => {
+                        return Err(err
+                            .context(format!("Failed to send event from {}", path.display()))
+                            .into());
+                    }
+                    BatchEvent::Failed(err) => failed.push((path.clone(), err)),
+                    BatchEvent::Skipped => summary.skipped += 1,
+                    BatchEvent::BelowMinLevel => summary.below_min_level += 1,
+                }
+            }
+        }
+
+        // files with several failed events are only counted once
+        summary.files_failed = failed.iter().map(|(path, _)| path).unique().count();
+        if total > 1
+            || verbose
+            || summary.rate_limit.is_some()
+            || matches.is_present("sample_rate")
+            || matches.is_present("min_level")
+        {
+            output.print_summary(&summary, started.elapsed());
+        }
+
+        let skipped = deadline_skipped.into_inner();
+        if !failed.is_empty() {
+            if skipped > 0 {
+                eprintln!(
+                    "{} {}",
+                    style("warning:").yellow(),
+                    DeadlineError { skipped, total }
+                );
+            }
+            let message = format!(
+                "Failed to process {} of {} event file(s)",
+                summary.files_failed, total
+            );
+            for (path, err) in &failed {
+                eprintln!(
+                    "{} {}: {}",
+                    style("error:").red(),
+                    path.display(),
+                    err.iter_chain().join(": ")
+                );
+            }
+            // keep the delivery failure around if that is all that went wrong
+            if failed.iter().all(|(_, err)| get_send_error(err).is_some()) {
+                let (_, err) = failed.swap_remove(0);
+                return Err(err.context(message).into());
+            }
+            bail!(message);
+        }
+
+        if skipped > 0 {
+            return Err(DeadlineError { skipped, total }.into());
         }
 
         return Ok(());
     }
 
+    if matches.is_present("strict_platform") {
+        validate_platform(matches.value_of("platform").unwrap_or("other"))?;
+    }
+
+    let template = match matches.value_of("template") {
+        Some(path) => read_template(Path::new(path))?,
+        None => Event::default(),
+    };
+
+    // skips everything that is not explicitly passed, except for SDK info
+    let defaults = !matches.is_present("no_default_tags");
+
+    let expand_env = if matches.is_present("expand_env") {
+        Some(matches.value_of("expand_env") == Some("lenient"))
+    } else {
+        None
+    };
+    let expand = |value: &str| match expand_env {
+        Some(lenient) => expand_env_vars(value, lenient),
+        None => Ok(value.to_owned()),
+    };
+
     let mut event = Event {
+        event_id: matches
+            .value_of("event_id")
+            .map(str::parse)
+            .transpose()?
+            .unwrap_or(template.event_id),
         sdk: Some(get_sdk_info()),
         level: matches
             .value_of("level")
             .and_then(|l| l.parse().ok())
-            .unwrap_or(Level::Error),
-        release: matches
-            .value_of("release")
+            .unwrap_or(template.level),
+        release: match (
+            matches.value_of("release"),
+            matches.value_of("release_file"),
+        ) {
+            (Some(release), _) => Some(Cow::from(release.to_owned())),
+            (None, Some(path)) => match read_release_file(Path::new(path))? {
+                Some(release) => Some(Cow::from(release)),
+                None => bail!("Release file {} is missing or empty", path),
+            },
+            (None, None)
+                if template.release.is_some()
+                    || !defaults
+                    || matches.is_present("no_release_detection") =>
+            {
+                template.release
+            }
+            // a checked-in VERSION file is only used if nothing else is detected
+            (None, None) => get_release_from_env(matches)
+                .or_else(|| detect_release_name_with_file(Path::new("VERSION")).ok())
+                .map(Cow::from),
+        },
+        dist: matches
+            .value_of("dist")
+            .map(|x| x.to_string().into())
+            .or(template.dist),
+        transaction: matches
+            .value_of("transaction_name")
             .map(str::to_owned)
-            .or_else(|| detect_release_name().ok())
-            .map(Cow::from),
-        dist: matches.value_of("dist").map(|x| x.to_string().into()),
+            .or(template.transaction),
+        culprit: matches
+            .value_of("culprit")
+            .map(str::to_owned)
+            .or(template.culprit),
         platform: matches
             .value_of("platform")
-            .unwrap_or("other")
-            .to_string()
-            .into(),
-        environment: matches
-            .value_of("environment")
-            .map(|x| x.to_string().into()),
-        logentry: matches.values_of("message").map(|mut lines| LogEntry {
-            message: lines.join("\n"),
-            params: matches
-                .values_of("message_args")
-                .map(|args| args.map(|x| x.into()).collect())
-                .unwrap_or_default(),
-        }),
-        ..Event::default()
+            .map(|x| x.to_string().into())
+            .unwrap_or(template.platform),
+        logger: matches
+            .value_of("logger")
+            .map(str::to_owned)
+            .or(template.logger),
+        environment: match (
+            matches.value_of("environment"),
+            matches.value_of("environment_file"),
+        ) {
+            (Some(environment), _) => Some(Cow::from(
+                validate_environment_name(environment)?.to_owned(),
+            )),
+            (None, Some(path)) => Some(Cow::from(read_environment_file(Path::new(path))?)),
+            (None, None) if template.environment.is_some() => template.environment,
+            (None, None) if matches.is_present("no_detect_environment") || !defaults => None,
+            (None, None) => detect_environment_name()
+                .filter(|name| match validate_environment_name(name) {
+                    Ok(_) => true,
+                    Err(err) => {
+                        warn!("Ignoring detected environment: {}", err);
+                        false
+                    }
+                })
+                .map(Cow::from),
+        },
+        server_name: if matches.is_present("no_server_name") {
+            None
+        } else {
+            matches
+                .value_of("server_name")
+                .map(|x| Cow::from(x.to_owned()))
+                .or(template.server_name)
+                .or_else(|| get_hostname().filter(|_| defaults).map(Cow::from))
+        },
+        logentry: match matches.value_of("message_file") {
+            Some(path) => Some(get_logentry(&read_message_file(path)?, matches)?),
+            None => matches
+                .values_of("message")
+                .map(|lines| {
+                    let lines = lines.map(expand).collect::<Result<Vec<_>, _>>()?;
+                    get_logentry(&lines.join("\n"), matches)
+                })
+                .transpose()?,
+        }
+        .or(template.logentry),
+        ..template
     };
 
+    if let Some(logentry) = event.logentry.as_mut() {
+        let limit = matches
+            .value_of("max_message_size")
+            .map(str::parse)
+            .transpose()?
+            .unwrap_or(MAX_MESSAGE_SIZE);
+        if truncate_message(&mut logentry.message, limit) {
+            warn!("Truncated the event message to {} bytes", limit);
+        }
+    }
+
+    if let Some(sdk) = event.sdk.as_mut() {
+        if let Some(name) = matches.value_of("sdk_name") {
+            sdk.to_mut().name = name.to_owned();
+        }
+        if let Some(version) = matches.value_of("sdk_version") {
+            sdk.to_mut().version = version.to_owned();
+        }
+    }
+
     if let Some(timestamp) = matches.value_of("timestamp") {
         event.timestamp = get_timestamp(timestamp)?;
     }
 
+    let delimiter = matches
+        .value_of("delimiter")
+        .and_then(|d| d.chars().next())
+        .unwrap_or(':');
+
+    if matches.is_present("git_enrich") {
+        match get_git_head() {
+            Ok((branch, sha)) => {
+                if let Some(branch) = branch {
+                    event.tags.insert("git.branch".into(), branch);
+                }
+                event.tags.insert("git.commit".into(), sha);
+            }
+            Err(err) if matches.is_present("strict_git") => {
+                return Err(err
+                    .context("Could not read the local git repository")
+                    .into());
+            }
+            Err(err) => debug!("Skipping git enrichment: {}", err),
+        }
+    }
+
+    if matches.is_present("tag_from_env") {
+        let (allowlist, denylist) = get_environ_patterns(matches)?;
+        for prefix in matches.values_of("tag_from_env").unwrap_or_default() {
+            event
+                .tags
+                .extend(get_env_tags(prefix, &allowlist, &denylist));
+        }
+    }
+
+    if let Some(path) = matches.value_of("tags_file") {
+        event
+            .tags
+            .extend(read_pairs_file(Path::new(path), delimiter, "tag")?);
+    }
+
+    if let Some(json) = matches.value_of("tags_json") {
+        event
+            .tags
+            .extend(parse_tags_json(json, matches.is_present("strict_tags"))?);
+    }
+
     for tag in matches.values_of("tags").unwrap_or_default() {
-        let mut split = tag.splitn(2, ':');
-        let key = split.next().ok_or_else(|| err_msg("missing tag key"))?;
-        let value = split.next().ok_or_else(|| err_msg("missing tag value"))?;
-        event.tags.insert(key.into(), value.into());
+        let (key, value) = split_pair(tag, delimiter, "tag")?;
+        event.tags.insert(key.into(), expand(value)?);
+    }
+
+    if matches.is_present("truncate_tags") {
+        for (key, value) in event.tags.iter_mut() {
+            if truncate_tag_value(value) {
+                warn!(
+                    "Truncated value of tag '{}' to {} characters",
+                    key, MAX_TAG_VALUE_LENGTH
+                );
+            }
+        }
+    }
+
+    if let Some(sha) = matches.value_of("commit") {
+        event.tags.insert("commit".into(), sha.into());
+        let mut commit = serde_json::Map::new();
+        commit.insert("sha".into(), Value::String(sha.into()));
+        if let Some(summary) = get_commit_summary(sha) {
+            commit.insert("message".into(), Value::String(summary));
+        }
+        event.extra.insert("commit".into(), Value::Object(commit));
     }
 
-    if !matches.is_present("no_environ") {
+    if matches.is_present("capture_args") {
+        let args = get_captured_args(env::args());
+        event.tags.insert(
+            "command".into(),
+            args.join(" ").chars().take(MAX_TAG_VALUE_LENGTH).collect(),
+        );
         event.extra.insert(
-            "environ".into(),
-            Value::Object(env::vars().map(|(k, v)| (k, Value::String(v))).collect()),
+            "command_line".into(),
+            Value::Array(args.into_iter().map(Value::String).collect()),
+        );
+    }
+
+    if !matches.is_present("no_environ") && defaults {
+        event
+            .extra
+            .insert("environ".into(), get_environ_from_matches(matches)?);
+    }
+
+    if !matches.is_present("no_contexts") && defaults {
+        event.contexts.extend(get_default_contexts());
+    }
+
+    if let Some(path) = matches.value_of("contexts_file") {
+        event.contexts.extend(read_contexts_file(Path::new(path))?);
+    }
+
+    if let Some(trace_id) = matches.value_of("trace_id") {
+        event.contexts.insert(
+            "trace".into(),
+            Context::Trace(Box::new(TraceContext {
+                trace_id: trace_id.parse()?,
+                span_id: Uuid::new_v4(),
+                parent_span_id: matches.value_of("parent_span_id").map(str::to_lowercase),
+                ..Default::default()
+            })),
         );
     }
 
+    if let Some(path) = matches.value_of("modules_file") {
+        event
+            .modules
+            .extend(read_pairs_file(Path::new(path), delimiter, "module")?);
+    }
+
+    for module in matches.values_of("modules").unwrap_or_default() {
+        let (name, version) = split_pair(module, delimiter, "module")?;
+        event.modules.insert(name.into(), version.into());
+    }
+
+    let infer_extra_types = matches.is_present("infer_extra_types");
     for pair in matches.values_of("extra").unwrap_or_default() {
-        let mut split = pair.splitn(2, ':');
-        let key = split.next().ok_or_else(|| err_msg("missing extra key"))?;
-        let value = split.next().ok_or_else(|| err_msg("missing extra value"))?;
-        event.extra.insert(key.into(), Value::String(value.into()));
+        let (key, value) = split_pair(pair, delimiter, "extra")?;
+        let value = expand(value)?;
+        let value = if infer_extra_types {
+            infer_extra_value(&value)
+        } else {
+            Value::String(value)
+        };
+        event.extra.insert(key.into(), value);
     }
 
-    if let Some(user_data) = matches.values_of("user_data") {
-        let mut user = User::default();
+    for pair in matches.values_of("extra_json").unwrap_or_default() {
+        let (key, value) = split_pair(pair, delimiter, "extra")?;
+        let value = serde_json::from_str(value)
+            .with_context(|_| format!("Invalid JSON for extra '{}'", key))?;
+        event.extra.insert(key.into(), value);
+    }
+
+    if matches.is_present("no_user") {
+        event.user = None;
+    } else if let Some(user_data) = matches.values_of("user_data") {
+        let mut user = event.user.take().unwrap_or_default();
         for pair in user_data {
-            let mut split = pair.splitn(2, ':');
-            let key = split.next().ok_or_else(|| err_msg("missing user key"))?;
-            let value = split.next().ok_or_else(|| err_msg("missing user value"))?;
+            let (key, value) = split_pair(pair, delimiter, "user")?;
 
-            match key {
+            match &*key {
                 "id" => user.id = Some(value.into()),
                 "email" => user.email = Some(value.into()),
-                "ip_address" => user.ip_address = Some(value.parse()?),
+                "ip_address" => user.ip_address = Some(parse_ip_address(value)?),
                 "username" => user.username = Some(value.into()),
                 _ => {
                     user.other.insert(key.into(), value.into());
@@ -262,27 +3301,214 @@ pub fn execute(matches: &ArgMatches<'_>) -> Result<(), Error> {
 
         user.ip_address.get_or_insert(Default::default());
         event.user = Some(user);
-    } else {
-        event.user = get_user_name().ok().map(|n| User {
-            username: Some(n),
-            ip_address: Some(Default::default()),
+    } else if event.user.is_none() && defaults {
+        let (username, email) = if matches.is_present("no_git_user") {
+            (None, None)
+        } else {
+            get_git_user()
+        };
+
+        event.user = if username.is_some() || email.is_some() {
+            Some(User {
+                username,
+                email,
+                ip_address: Some(Default::default()),
+                ..Default::default()
+            })
+        } else {
+            get_user_name().ok().map(|n| User {
+                username: Some(n),
+                ip_address: Some(Default::default()),
+                ..Default::default()
+            })
+        };
+    }
+
+    if matches.is_present("request_url")
+        || matches.is_present("request_method")
+        || matches.is_present("request_headers")
+    {
+        let mut request = event.request.take().unwrap_or_default();
+        if let Some(url) = matches.value_of("request_url") {
+            request.url = Some(url.parse()?);
+        }
+        if let Some(method) = matches.value_of("request_method") {
+            request.method = Some(parse_request_method(
+                method,
+                matches.is_present("strict_request_method"),
+            )?);
+        }
+        for header in matches.values_of("request_headers").unwrap_or_default() {
+            let (key, value) = split_pair(header, delimiter, "request header")?;
+            request.headers.insert(key.into(), value.into());
+        }
+        event.request = Some(request);
+    }
+
+    let mut fingerprint: Vec<Cow<'_, str>> = matches
+        .values_of("fingerprint")
+        .unwrap_or_default()
+        .map(|x| x.to_string().into())
+        .collect();
+    if let Some(path) = matches.value_of("fingerprint_from_file") {
+        let file = File::open(path)
+            .with_context(|_| format!("Could not read fingerprint file {}", path))?;
+        fingerprint.push(get_sha1_checksum(file)?.to_string().into());
+    }
+    if !fingerprint.is_empty() {
+        event.fingerprint = fingerprint.into();
+    }
+
+    if let Some(logfiles) = matches.values_of("logfile") {
+        let options = LogfileOptions {
+            with_component: matches.is_present("with_categories"),
+            category_levels: matches
+                .values_of("category_levels")
+                .unwrap_or_default()
+                .map(parse_category_level)
+                .collect::<Result<_, _>>()?,
+            category_pattern: matches
+                .value_of("category_pattern")
+                .map(parse_category_pattern)
+                .transpose()?,
+            limit: matches
+                .value_of("logfile_limit")
+                .map(str::parse)
+                .transpose()?
+                .unwrap_or(DEFAULT_LOGFILE_LIMIT),
+            since: matches
+                .value_of("breadcrumbs_since")
+                .map(get_timestamp)
+                .transpose()?,
+        };
+        for logfile in logfiles {
+            attach_logfile(&mut event, logfile, &options)?;
+        }
+    }
+
+    for breadcrumb in matches.values_of("breadcrumbs").unwrap_or_default() {
+        event.breadcrumbs.values.push(parse_breadcrumb(breadcrumb)?);
+    }
+
+    // --logfile-limit 0 asks for all records, so it also lifts the default cap
+    let max_breadcrumbs = match matches.value_of("max_breadcrumbs") {
+        Some(max) => max.parse()?,
+        None if matches.value_of("logfile_limit") == Some("0") => 0,
+        None => MAX_BREADCRUMBS,
+    };
+    if max_breadcrumbs > 0 && event.breadcrumbs.len() > max_breadcrumbs {
+        let skip = event.breadcrumbs.len() - max_breadcrumbs;
+        event.breadcrumbs.values.drain(..skip);
+        debug!("Dropped {} of the oldest breadcrumbs", skip);
+    }
+
+    if let Some(path) = matches.value_of("stacktrace_file") {
+        event.exception.values.push(Exception {
+            ty: "Error".into(),
+            value: event.logentry.as_ref().map(|entry| entry.message.clone()),
+            stacktrace: Some(read_stacktrace(Path::new(path))?),
             ..Default::default()
         });
     }
 
-    if let Some(fingerprint) = matches.values_of("fingerprint") {
-        event.fingerprint = fingerprint
-            .map(|x| x.to_string().into())
-            .collect::<Vec<_>>()
-            .into();
+    for value in matches.values_of("threads").unwrap_or_default() {
+        let thread = parse_thread(value)?;
+        if event.threads.values.iter().any(|t| t.id == thread.id) {
+            bail!("Duplicate thread id in '{}'", value);
+        }
+        event.threads.values.push(thread);
     }
 
-    if let Some(logfile) = matches.value_of("logfile") {
-        attach_logfile(&mut event, logfile, matches.is_present("with_categories"))?;
+    let mut attachments = vec![];
+    for path in matches.values_of("attachments").unwrap_or_default() {
+        attachments.extend(read_attachment(Path::new(path))?);
     }
+    for value in matches.values_of("attachment_strings").unwrap_or_default() {
+        attachments.extend(parse_attachment_string(value)?);
+    }
+    if let Some(path) = matches.value_of("minidump") {
+        attachments.push(read_minidump(Path::new(path))?);
+    }
+
+    // check the event once it is complete, including stack traces and threads
+    check_event_limits(&event, matches.is_present("strict_limits"))?;
+    if let Some(ref schema) = schema {
+        check_event_schema(&event, schema)?;
+    }
+
+    let additional_dsns = matches
+        .values_of("additional_dsns")
+        .unwrap_or_default()
+        .map(str::parse)
+        .collect::<Result<Vec<Dsn>, _>>()?;
+    let repeat = matches
+        .value_of("repeat")
+        .map(str::parse)
+        .transpose()?
+        .unwrap_or(1);
+    let repeat_delay = Duration::from_millis(
+        matches
+            .value_of("repeat_delay")
+            .map(str::parse)
+            .transpose()?
+            .unwrap_or(0),
+    );
+    if !matches.is_present("confirm_repeat") {
+        let threshold = get_confirm_threshold(matches)?;
+        if repeat > threshold
+            && !confirm_large_send(matches, &format!("Send the event {} times?", repeat))?
+        {
+            bail!(
+                "Refusing to send the event more than {} times without --confirm-repeat",
+                threshold
+            );
+        }
+    }
+
+    let print_curl = if matches.is_present("print_curl") {
+        Some(matches.value_of("print_curl") == Some("full"))
+    } else {
+        None
+    };
+
+    for i in 0..repeat {
+        let mut event = event.clone();
+        if i > 0 {
+            // every repetition is sent as a distinct event
+            event.event_id = Uuid::new_v4();
+            thread::sleep(repeat_delay);
+        }
 
-    let id = send_raw_event(event, dsn);
-    println!("Event dispatched: {}", id);
+        if dry_run || print_curl.is_some() {
+            if dry_run {
+                output.print_event(None, &event)?;
+            }
+            if let Some(full) = print_curl {
+                let mut envelope = Envelope::from(event);
+                for attachment in &attachments {
+                    envelope.add_item(EnvelopeItem::Attachment(attachment.clone()));
+                }
+                let body = serialize_envelope(&envelope)?;
+                println!("{}", get_curl_command(&dsn, &body, &send_options, full));
+            }
+        } else if !additional_dsns.is_empty() {
+            let mut dsns = vec![dsn.clone()];
+            dsns.extend(additional_dsns.iter().cloned());
+            send_to_all_dsns(event, attachments.clone(), dsns, &send_options, output)?;
+        } else {
+            let id = if attachments.is_empty() {
+                send_raw_event(event, dsn.clone(), &send_options)?
+            } else {
+                send_raw_event_with_attachments(
+                    event,
+                    attachments.clone(),
+                    dsn.clone(),
+                    &send_options,
+                )?
+            };
+            output.print_dispatched(None, id);
+        }
+    }
 
     Ok(())
 }
@@ -0,0 +1,196 @@
+//! Implements a command for sending performance transactions to Sentry.
+use chrono::{DateTime, Duration, Utc};
+use clap::{App, Arg, ArgMatches};
+use failure::{bail, Error};
+use sentry::protocol::{Context, Span, TraceContext, Transaction};
+use sentry::types::Uuid;
+
+use crate::config::Config;
+use crate::utils::args::{get_timestamp, validate_timestamp};
+use crate::utils::event::{get_sdk_info, normalize_payload, send_envelope, SendOptions};
+
+pub fn make_app<'a, 'b: 'a>(app: App<'a, 'b>) -> App<'a, 'b> {
+    app.about("Send a manual performance transaction to Sentry.")
+        .arg(
+            Arg::with_name("name")
+                .value_name("NAME")
+                .index(1)
+                .required(true)
+                .help("The name of the transaction."),
+        )
+        .arg(
+            Arg::with_name("op")
+                .value_name("OP")
+                .long("op")
+                .help("The operation of the transaction. [defaults to 'default']"),
+        )
+        .arg(
+            Arg::with_name("status")
+                .value_name("STATUS")
+                .long("status")
+                .help("The status of the transaction. [defaults to 'ok']"),
+        )
+        .arg(
+            Arg::with_name("start")
+                .value_name("TIMESTAMP")
+                .long("start")
+                .validator(validate_timestamp)
+                .allow_hyphen_values(true)
+                .help(
+                    "The start of the transaction.  Defaults to the end minus the total \
+                     duration of all spans.",
+                ),
+        )
+        .arg(
+            Arg::with_name("end")
+                .value_name("TIMESTAMP")
+                .long("end")
+                .validator(validate_timestamp)
+                .allow_hyphen_values(true)
+                .help(
+                    "The end of the transaction.  Defaults to the start plus the total \
+                     duration of all spans, or now.",
+                ),
+        )
+        .arg(
+            Arg::with_name("spans")
+                .value_name("OP:DURATION_MS")
+                .long("span")
+                .multiple(true)
+                .number_of_values(1)
+                .validator(validate_span)
+                .help(
+                    "Add a span with the given operation and duration in milliseconds.  \
+                     Spans are laid out one after another from the start of the transaction.",
+                ),
+        )
+        .arg(
+            Arg::with_name("tags")
+                .value_name("KEY:VALUE")
+                .long("tag")
+                .short("t")
+                .multiple(true)
+                .number_of_values(1)
+                .help("Add a tag (key:value) to the transaction."),
+        )
+        .arg(
+            Arg::with_name("dry_run")
+                .long("dry-run")
+                .help("Print the transaction as JSON instead of sending it."),
+        )
+}
+
+fn validate_span(v: String) -> Result<(), String> {
+    parse_span(&v).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Parses a span given as `op:duration_ms`.
+fn parse_span(value: &str) -> Result<(String, Duration), Error> {
+    let mut split = value.rsplitn(2, ':');
+    let duration = split.next().unwrap_or_default();
+    let op = match split.next() {
+        Some(op) if !op.is_empty() => op,
+        _ => bail!("Invalid span '{}' (expected op:duration_ms)", value),
+    };
+    match duration.parse::<u32>() {
+        Ok(ms) => Ok((op.to_string(), Duration::milliseconds(ms.into()))),
+        Err(_) => bail!("Invalid duration in span '{}'", value),
+    }
+}
+
+/// Formats a span id the way Sentry expects it: 16 hex characters.
+fn span_id(uuid: &Uuid) -> String {
+    uuid.to_simple_ref().to_string()[..16].to_string()
+}
+
+fn get_time_range(
+    matches: &ArgMatches<'_>,
+    total: Duration,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), Error> {
+    let start = matches.value_of("start").map(get_timestamp).transpose()?;
+    let end = matches.value_of("end").map(get_timestamp).transpose()?;
+    let (start, end) = match (start, end) {
+        (Some(start), Some(end)) => (start, end),
+        (Some(start), None) => (start, start + total),
+        (None, Some(end)) => (end - total, end),
+        (None, None) => {
+            let end = Utc::now();
+            (end - total, end)
+        }
+    };
+
+    if end < start {
+        bail!("The transaction cannot end before it starts");
+    }
+    Ok((start, end))
+}
+
+pub fn execute(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    let spans = matches
+        .values_of("spans")
+        .unwrap_or_default()
+        .map(parse_span)
+        .collect::<Result<Vec<_>, _>>()?;
+    let total = spans
+        .iter()
+        .fold(Duration::zero(), |total, (_, duration)| total + *duration);
+    let (start, end) = get_time_range(matches, total)?;
+
+    let trace_id = Uuid::new_v4();
+    let root_span_id = Uuid::new_v4();
+
+    let mut transaction = Transaction {
+        name: matches.value_of("name").map(str::to_owned),
+        sdk: Some(get_sdk_info()),
+        start_timestamp: start,
+        timestamp: Some(end),
+        ..Transaction::new()
+    };
+
+    let mut span_start = start;
+    for (op, duration) in spans {
+        transaction.spans.push(Span {
+            trace_id,
+            parent_span_id: Some(span_id(&root_span_id)),
+            op: Some(op),
+            start_timestamp: span_start,
+            timestamp: Some(span_start + duration),
+            ..Span::new()
+        });
+        span_start = span_start + duration;
+    }
+
+    transaction.contexts.insert(
+        "trace".into(),
+        Context::Trace(Box::new(TraceContext {
+            span_id: root_span_id,
+            trace_id,
+            op: Some(matches.value_of("op").unwrap_or("default").to_string()),
+            status: Some(matches.value_of("status").unwrap_or("ok").to_string()),
+            ..Default::default()
+        })),
+    );
+
+    for tag in matches.values_of("tags").unwrap_or_default() {
+        let mut split = tag.splitn(2, ':');
+        let key = split.next().unwrap_or_default();
+        match split.next() {
+            Some(value) => transaction.tags.insert(key.into(), value.into()),
+            None => bail!("missing tag value in '{}' (expected key:value)", tag),
+        };
+    }
+
+    if matches.is_present("dry_run") {
+        let mut payload = serde_json::to_value(&transaction)?;
+        normalize_payload(&mut payload, true);
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    let id = transaction.event_id;
+    let dsn = Config::current().get_dsn()?;
+    send_envelope(&dsn, &transaction.into(), &SendOptions::default())?;
+    println!("Transaction dispatched: {}", id);
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! Implements a command for checking that the DSN accepts events.
+use std::time::Duration;
+
+use clap::{App, Arg, ArgMatches};
+use console::style;
+use failure::{Error, ResultExt};
+
+use crate::config::Config;
+use crate::utils::event::{
+    check_envelope_endpoint, get_send_error, get_status_error_kind, SendOptions, EXIT_AUTH,
+};
+use crate::utils::system::{print_error, QuietExit};
+
+pub fn make_app<'a, 'b: 'a>(app: App<'a, 'b>) -> App<'a, 'b> {
+    app.about("Check that the DSN accepts events without sending one.")
+        .long_about(
+            "Check that the DSN accepts events without sending one.{n}{n}\
+             Sends an empty envelope to the project of the configured DSN and reports \
+             whether it was accepted along with the rate limits of the server.  Use this \
+             as a preflight check before long running jobs.  The command exits with code \
+             2 if the project is rate limited, 3 on network or server errors, 5 if the \
+             DSN is missing or access was denied and 1 if the envelope was rejected for \
+             other reasons.",
+        )
+        .arg(
+            Arg::with_name("timeout")
+                .value_name("SECONDS")
+                .long("timeout")
+                .validator(|v| match v.parse::<u64>() {
+                    Ok(n) if n > 0 => Ok(()),
+                    _ => Err("Invalid timeout, positive number of seconds required.".into()),
+                })
+                .help("The maximum time in seconds to wait for the server. [defaults to 30]"),
+        )
+}
+
+fn check_dsn(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    let dsn = Config::current()
+        .get_dsn()
+        .context("No valid DSN configured")
+        .map_err(|err| {
+            print_error(&err.into());
+            QuietExit(EXIT_AUTH)
+        })?;
+
+    let mut options = SendOptions::default();
+    if let Some(timeout) = matches.value_of("timeout") {
+        options.timeout = Duration::from_secs(timeout.parse()?);
+    }
+
+    // never print the key of the DSN
+    println!("Host: {}", dsn.host());
+    println!("Project: {}", dsn.project_id());
+
+    let response = check_envelope_endpoint(&dsn, &options)?;
+    let status = response.status();
+    println!(
+        "Rate limits: {}",
+        response
+            .get_header("x-sentry-rate-limits")
+            .unwrap_or("none")
+    );
+    if let Some(retry_after) = response.get_header("retry-after") {
+        println!("Retry after: {}s", retry_after);
+    }
+
+    if response.ok() {
+        println!("Status: {} ({})", style("accepted").green(), status);
+        return Ok(());
+    }
+
+    let kind = get_status_error_kind(status);
+    println!("Status: {} ({})", style(kind).red(), status);
+    Err(QuietExit(kind.exit_code()).into())
+}
+
+pub fn execute(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    check_dsn(matches).map_err(|err| match get_send_error(&err) {
+        Some(send_error) => {
+            print_error(&err);
+            QuietExit(send_error.kind.exit_code()).into()
+        }
+        None => err,
+    })
+}
@@ -0,0 +1,123 @@
+//! Implements a command for validating event files without sending them.
+use std::path::{Path, PathBuf};
+
+use clap::{App, Arg, ArgMatches};
+use console::style;
+use failure::{bail, Error};
+use glob::{glob_with, MatchOptions};
+use itertools::Itertools;
+use log::warn;
+use serde_json::Value;
+
+use crate::utils::event::{check_event_limits, check_event_schema, read_schema};
+use crate::utils::event_file::{get_path_pattern, read_events, FileFormat};
+
+pub fn make_app<'a, 'b: 'a>(app: App<'a, 'b>) -> App<'a, 'b> {
+    app.about("Validate event files without sending them.")
+        .long_about(
+            "Validate event files without sending them.{n}{n}\
+             Every file is parsed like send-event does and checked against the limits of \
+             Sentry like with --strict-limits.  This needs neither network access nor a \
+             DSN, which makes it usable in pre-commit hooks.  The command fails if any file \
+             is invalid.",
+        )
+        .arg(
+            Arg::with_name("path")
+                .value_name("PATH")
+                .index(1)
+                .required(true)
+                .help(
+                    "The path or glob to the event file(s).  Directories match all .json \
+                     files in them.",
+                ),
+        )
+        .arg(
+            Arg::with_name("recursive")
+                .long("recursive")
+                .help("When the path is a directory, also validate its subdirectories."),
+        )
+        .arg(
+            Arg::with_name("ndjson")
+                .long("ndjson")
+                .help("Treat the event file(s) as newline-delimited JSON with one event per line."),
+        )
+        .arg(
+            Arg::with_name("format")
+                .value_name("FORMAT")
+                .long("format")
+                .conflicts_with("ndjson")
+                .possible_values(&["json", "yaml"])
+                .help(
+                    "The format of the event file(s).  Files ending in .yaml or .yml are \
+                     read as YAML, everything else as JSON. [defaults to detection]",
+                ),
+        )
+        .arg(
+            Arg::with_name("schema")
+                .value_name("PATH")
+                .long("schema")
+                .help("Also validate every event against this JSON schema."),
+        )
+}
+
+/// Validates all events in a file and returns their number.
+fn validate_file(
+    path: &Path,
+    ndjson: bool,
+    format: Option<FileFormat>,
+    schema: Option<&Value>,
+) -> Result<usize, Error> {
+    let events = read_events(path, ndjson, format)?;
+    for event in &events {
+        check_event_limits(event, true)?;
+        if let Some(schema) = schema {
+            check_event_schema(event, schema)?;
+        }
+    }
+    Ok(events.len())
+}
+
+pub fn execute(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    let path = matches.value_of("path").unwrap();
+    let pattern = get_path_pattern(path, matches.is_present("recursive"));
+    let paths: Vec<PathBuf> = glob_with(&pattern, MatchOptions::new())?
+        .flatten()
+        .collect();
+    if paths.is_empty() {
+        warn!("Did not match any files for pattern: {}", pattern);
+        return Ok(());
+    }
+
+    let ndjson = matches.is_present("ndjson");
+    let format = FileFormat::from_matches(matches);
+    let schema = matches
+        .value_of("schema")
+        .map(|path| read_schema(Path::new(path)))
+        .transpose()?;
+
+    let mut failed = 0;
+    for path in &paths {
+        match validate_file(path, ndjson, format, schema.as_ref()) {
+            Ok(count) => println!(
+                "{} {} ({} event(s))",
+                style("ok").green(),
+                path.display(),
+                count
+            ),
+            Err(err) => {
+                failed += 1;
+                println!(
+                    "{} {}: {}",
+                    style("failed").red(),
+                    path.display(),
+                    err.iter_chain().join(": ")
+                );
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!("{} of {} file(s) failed validation", failed, paths.len());
+    }
+    Ok(())
+}
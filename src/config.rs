@@ -41,6 +41,11 @@ pub struct Config {
     cached_base_url: String,
     cached_log_level: log::LevelFilter,
     cached_vcs_remote: String,
+    dsn: Option<Dsn>,
+    dsn_file: Option<PathBuf>,
+    http_proxy: Option<String>,
+    https_proxy: Option<String>,
+    profile: Option<String>,
 }
 
 impl Config {
@@ -50,6 +55,12 @@ impl Config {
         Config::from_file(filename, ini)
     }
 
+    /// Loads the CLI config from the given file instead of the default location.
+    pub fn from_path(path: &Path) -> Result<Config, Error> {
+        let (filename, ini) = load_config_file(path)?;
+        Config::from_file(filename, ini)
+    }
+
     /// Creates Config based on provided config file.
     pub fn from_file(filename: PathBuf, ini: Ini) -> Result<Config, Error> {
         Ok(Config {
@@ -59,6 +70,11 @@ impl Config {
             cached_base_url: get_default_url(&ini),
             cached_log_level: get_default_log_level(&ini),
             cached_vcs_remote: get_default_vcs_remote(&ini),
+            dsn: None,
+            dsn_file: None,
+            http_proxy: None,
+            https_proxy: None,
+            profile: None,
             ini,
         })
     }
@@ -163,6 +179,37 @@ impl Config {
         }
     }
 
+    /// Selects a named profile from the `[profile.NAME]` section of the config
+    /// file.
+    ///
+    /// The DSN, auth token, org and project of the profile take precedence
+    /// over the environment and the rest of the config file, but not over
+    /// command line arguments.
+    pub fn set_profile(&mut self, name: &str) -> Result<(), Error> {
+        let section = format!("profile.{}", name);
+        if self.ini.section(Some(section.as_str())).is_none() {
+            bail!(
+                "Profile '{}' not found in config file ({})",
+                name,
+                self.filename.display()
+            );
+        }
+
+        if let Some(val) = self.ini.get_from(Some(section.as_str()), "token") {
+            self.cached_auth = Some(Auth::Token(val.to_owned()));
+        } else if let Some(val) = self.ini.get_from(Some(section.as_str()), "api_key") {
+            self.cached_auth = Some(Auth::Key(val.to_owned()));
+        }
+        self.profile = Some(section);
+        Ok(())
+    }
+
+    /// Returns a value from the selected profile.
+    fn get_profile_value(&self, key: &str) -> Option<&str> {
+        let section = self.profile.as_deref()?;
+        self.ini.get_from(Some(section), key)
+    }
+
     /// Returns the base url (without trailing slashes)
     pub fn get_base_url(&self) -> Result<&str, Error> {
         let base = self.cached_base_url.trim_end_matches('/');
@@ -224,6 +271,29 @@ impl Config {
         }
     }
 
+    /// Returns the proxy URL for a request to the given URL if defined.
+    ///
+    /// Proxies set on the command line take precedence over the general
+    /// proxy URL, but only apply to URLs of their scheme.
+    pub fn get_proxy_url_for(&self, url: &str) -> Option<String> {
+        let proxy = if url.starts_with("https://") {
+            &self.https_proxy
+        } else {
+            &self.http_proxy
+        };
+        proxy.clone().or_else(|| self.get_proxy_url())
+    }
+
+    /// Sets the proxy for http requests.
+    pub fn set_http_proxy(&mut self, proxy_url: &str) {
+        self.http_proxy = Some(proxy_url.to_owned());
+    }
+
+    /// Sets the proxy for https requests.
+    pub fn set_https_proxy(&mut self, proxy_url: &str) {
+        self.https_proxy = Some(proxy_url.to_owned());
+    }
+
     /// Returns the proxy username if defined.
     pub fn get_proxy_username(&self) -> Option<&str> {
         self.ini.get_from(Some("http"), "proxy_username")
@@ -272,6 +342,7 @@ impl Config {
         matches
             .value_of("org")
             .map(str::to_owned)
+            .or_else(|| self.get_profile_value("org").map(str::to_owned))
             .or_else(|| env::var("SENTRY_ORG").ok())
             .or_else(|| {
                 self.ini
@@ -296,8 +367,9 @@ impl Config {
 
     /// Return the default value for a project.
     pub fn get_project_default(&self) -> Result<String, Error> {
-        env::var("SENTRY_PROJECT")
-            .ok()
+        self.get_profile_value("project")
+            .map(str::to_owned)
+            .or_else(|| env::var("SENTRY_PROJECT").ok())
             .or_else(|| {
                 self.ini
                     .get_from(Some("defaults"), "project")
@@ -327,16 +399,15 @@ impl Config {
     /// Returns the defaults for org and project.
     pub fn get_org_and_project_defaults(&self) -> (Option<String>, Option<String>) {
         (
-            env::var("SENTRY_ORG").ok().or_else(|| {
-                self.ini
-                    .get_from(Some("defaults"), "org")
-                    .map(str::to_owned)
-            }),
-            env::var("SENTRY_PROJECT").ok().or_else(|| {
-                self.ini
-                    .get_from(Some("defaults"), "project")
-                    .map(str::to_owned)
-            }),
+            self.get_profile_value("org")
+                .map(str::to_owned)
+                .or_else(|| env::var("SENTRY_ORG").ok())
+                .or_else(|| {
+                    self.ini
+                        .get_from(Some("defaults"), "org")
+                        .map(str::to_owned)
+                }),
+            self.get_project_default().ok(),
         )
     }
 
@@ -368,10 +439,32 @@ impl Config {
         }
     }
 
+    /// Sets the DSN explicitly.  This takes precedence over all other
+    /// sources of the DSN.
+    pub fn set_dsn(&mut self, dsn: Dsn) {
+        self.dsn = Some(dsn);
+    }
+
+    /// Sets a file to read the DSN from.  This takes precedence over the
+    /// environment and the config file.
+    pub fn set_dsn_file(&mut self, path: &Path) {
+        self.dsn_file = Some(path.to_path_buf());
+    }
+
     /// Return the DSN
     pub fn get_dsn(&self) -> Result<Dsn, Error> {
-        if let Ok(val) = env::var("SENTRY_DSN") {
+        if let Some(ref dsn) = self.dsn {
+            Ok(dsn.clone())
+        } else if let Some(ref path) = self.dsn_file {
+            read_dsn_file(path)
+        } else if let Some(path) = self.get_profile_value("dsn_file") {
+            read_dsn_file(Path::new(path))
+        } else if let Some(val) = self.get_profile_value("dsn") {
+            Ok(val.parse()?)
+        } else if let Ok(val) = env::var("SENTRY_DSN") {
             Ok(val.parse()?)
+        } else if let Some(path) = self.ini.get_from(Some("auth"), "dsn_file") {
+            read_dsn_file(Path::new(path))
         } else if let Some(val) = self.ini.get_from(Some("auth"), "dsn") {
             Ok(val.parse()?)
         } else {
@@ -469,6 +562,23 @@ fn load_global_config_file() -> Result<(PathBuf, Ini), Error> {
     }
 }
 
+fn read_dsn_file(path: &Path) -> Result<Dsn, Error> {
+    let contents = fs::read_to_string(path)
+        .with_context(|_| format!("Failed to read DSN file ({})", path.display()))?;
+    Ok(contents
+        .trim()
+        .parse::<Dsn>()
+        .with_context(|_| format!("Invalid DSN in file ({})", path.display()))?)
+}
+
+fn load_config_file(path: &Path) -> Result<(PathBuf, Ini), Error> {
+    let mut f = fs::File::open(path)
+        .with_context(|_| format!("Failed to load config file ({})", path.display()))?;
+    let ini = Ini::read_from(&mut f)
+        .with_context(|_| format!("Failed to parse config file ({})", path.display()))?;
+    Ok((path.to_path_buf(), ini))
+}
+
 fn load_cli_config() -> Result<(PathBuf, Ini), Error> {
     let (global_filename, mut rv) = load_global_config_file()?;
 
@@ -534,6 +644,11 @@ impl Clone for Config {
             cached_base_url: self.cached_base_url.clone(),
             cached_log_level: self.cached_log_level,
             cached_vcs_remote: self.cached_vcs_remote.clone(),
+            dsn: self.dsn.clone(),
+            dsn_file: self.dsn_file.clone(),
+            http_proxy: self.http_proxy.clone(),
+            https_proxy: self.https_proxy.clone(),
+            profile: self.profile.clone(),
         }
     }
 }
@@ -49,8 +49,58 @@ pub const DEFAULT_INITIAL_INTERVAL: u64 = 1000;
 pub const DEFAULT_MAX_INTERVAL: u64 = 5000;
 /// Default number of retry attempts
 pub const DEFAULT_RETRIES: u32 = 5;
+/// Default time in seconds to spend sending an event, including retries.
+pub const DEFAULT_SEND_TIMEOUT: u64 = 30;
 /// Default maximum file size of DIF uploads.
 pub const DEFAULT_MAX_DIF_SIZE: u64 = 2 * 1024 * 1024 * 1024; // 2GB
+/// Maximum file size of attachments sent along with events.
+pub const MAX_ATTACHMENT_SIZE: u64 = 20 * 1024 * 1024; // 20MB
+/// HTTP methods accepted for the request of events with --strict-request-method.
+pub const KNOWN_HTTP_METHODS: &[&str] = &[
+    "CONNECT", "DELETE", "GET", "HEAD", "OPTIONS", "PATCH", "POST", "PUT", "TRACE",
+];
+/// Platform identifiers accepted by Sentry for events.
+pub const KNOWN_PLATFORMS: &[&str] = &[
+    "as3",
+    "c",
+    "cfml",
+    "cocoa",
+    "csharp",
+    "elixir",
+    "go",
+    "groovy",
+    "haskell",
+    "java",
+    "javascript",
+    "native",
+    "node",
+    "objc",
+    "other",
+    "perl",
+    "php",
+    "python",
+    "ruby",
+];
+/// Maximum number of tags sent with an event before warning.
+pub const MAX_EVENT_TAGS: usize = 50;
+/// Maximum length of tag keys accepted by Sentry.
+pub const MAX_TAG_KEY_LENGTH: usize = 32;
+/// Maximum length of tag values accepted by Sentry.
+pub const MAX_TAG_VALUE_LENGTH: usize = 200;
+/// Maximum number of breadcrumbs kept by Sentry.
+pub const MAX_BREADCRUMBS: usize = 100;
+/// Maximum serialized size of extra data before warning.
+pub const MAX_EXTRA_SIZE: usize = 256 * 1024; // 256KB
+/// Maximum length of environment names accepted by Sentry.
+pub const MAX_ENVIRONMENT_LENGTH: usize = 64;
+/// Characters other than whitespace that Sentry rejects in environment names.
+pub const INVALID_ENVIRONMENT_CHARS: &[char] = &['/'];
+/// An environment name that Sentry reserves.
+pub const RESERVED_ENVIRONMENT_NAME: &str = "None";
+/// Maximum size of event messages in bytes accepted by Sentry.
+pub const MAX_MESSAGE_SIZE: usize = 8192;
+/// Number of event files or repetitions sent without confirmation by default.
+pub const DEFAULT_CONFIRM_THRESHOLD: usize = 100;
 /// Default maximum time to wait for file assembly.
 pub const DEFAULT_MAX_WAIT: Duration = Duration::from_secs(5 * 60);
 
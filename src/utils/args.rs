@@ -2,10 +2,11 @@
 
 use std::str::FromStr;
 
-use chrono::{DateTime, TimeZone, Utc};
+use chrono::{DateTime, Duration, TimeZone, Utc};
 use clap::AppSettings;
 use failure::{bail, Error};
 use symbolic::common::DebugId;
+use url::Url;
 use uuid::Uuid;
 
 fn validate_org(v: String) -> Result<(), String> {
@@ -84,6 +85,37 @@ pub fn validate_id(s: String) -> Result<(), String> {
     }
 }
 
+/// Checks that the value is an http, https or socks proxy URL with a host.
+pub fn validate_proxy_url(v: String) -> Result<(), String> {
+    match Url::parse(&v) {
+        Ok(url)
+            if ["http", "https", "socks4", "socks4a", "socks5", "socks5h"]
+                .contains(&url.scheme())
+                && url.has_host() =>
+        {
+            Ok(())
+        }
+        Ok(_) => Err("Invalid proxy URL, expected an http, https or socks URL".to_string()),
+        Err(err) => Err(format!("Invalid proxy URL: {}", err)),
+    }
+}
+
+/// Parses a relative timestamp in the past such as `-5m` (seconds, minutes,
+/// hours or days).
+fn get_relative_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.strip_prefix('-')?;
+    let unit = value.chars().last()?;
+    let amount = i64::from(value[..value.len() - unit.len_utf8()].parse::<u32>().ok()?);
+    let seconds = match unit {
+        's' => amount,
+        'm' => amount * 60,
+        'h' => amount * 60 * 60,
+        'd' => amount * 60 * 60 * 24,
+        _ => return None,
+    };
+    Utc::now().checked_sub_signed(Duration::seconds(seconds))
+}
+
 pub fn get_timestamp(value: &str) -> Result<DateTime<Utc>, Error> {
     if let Ok(int) = value.parse::<i64>() {
         Ok(Utc.timestamp(int, 0))
@@ -91,8 +123,13 @@ pub fn get_timestamp(value: &str) -> Result<DateTime<Utc>, Error> {
         Ok(dt.with_timezone(&Utc))
     } else if let Ok(dt) = DateTime::parse_from_rfc2822(value) {
         Ok(dt.with_timezone(&Utc))
+    } else if let Some(dt) = get_relative_timestamp(value) {
+        Ok(dt)
     } else {
-        bail!("Not in valid format. Unix timestamp or ISO 8601 date expected.");
+        bail!(
+            "Not in valid format. Unix timestamp, ISO 8601 date or relative time \
+             (eg: -5m, -2h, -1d) expected."
+        );
     }
 }
 
@@ -154,3 +191,22 @@ impl<'a: 'b, 'b> ArgExt for clap::App<'a, 'b> {
         )
     }
 }
+
+#[test]
+fn test_get_relative_timestamp() {
+    let now = Utc::now();
+
+    let ts = get_timestamp("-90s").unwrap();
+    assert!((now - ts - Duration::seconds(90)).num_seconds().abs() <= 1);
+
+    let ts = get_timestamp("-0m").unwrap();
+    assert!((now - ts).num_seconds().abs() <= 1);
+
+    let ts = get_timestamp("-2h").unwrap();
+    assert!((now - ts - Duration::hours(2)).num_seconds().abs() <= 1);
+
+    assert!(get_timestamp("-5x").is_err());
+    assert!(get_timestamp("-m").is_err());
+    assert!(get_timestamp("5m").is_err());
+    assert!(validate_timestamp("-1d".to_string()).is_ok());
+}
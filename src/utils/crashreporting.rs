@@ -9,7 +9,8 @@ use sentry::{release_name, Client, ClientOptions, Hub};
 use crate::config::Config;
 use crate::constants::USER_AGENT;
 
-pub fn setup(_log: Box<dyn Log>) {
+pub fn setup(log: Box<dyn Log>) {
+    log::set_boxed_logger(log).ok();
     bind_configured_client(None);
 }
 
@@ -1,27 +1,111 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::env;
 use std::fs;
-use std::io::{BufRead, BufReader};
-use std::time::Duration;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
-use chrono::Utc;
-use failure::{Error, ResultExt};
+use backoff::backoff::Backoff;
+use chrono::{DateTime, Utc};
+use failure::{bail, Error, Fail, ResultExt};
+use flate2::write::GzEncoder;
+use indicatif::HumanBytes;
+use itertools::Itertools;
 use lazy_static::lazy_static;
+use log::warn;
+use parking_lot::{Condvar, Mutex};
 use regex::Regex;
-use sentry::protocol::{Breadcrumb, ClientSdkInfo, Event};
-use sentry::types::Dsn;
-use sentry::{apply_defaults, Client, ClientOptions};
+use sentry::protocol::{
+    Breadcrumb, ClientSdkInfo, Context, Envelope, EnvelopeItem, Event, Level, Map, OsContext,
+    RuntimeContext,
+};
+use sentry::types::{Dsn, Uuid};
+use sentry::{apply_defaults, Client, ClientOptions, Transport};
+use serde::Serialize;
+use serde_json::Value;
+use url::Url;
 
-use crate::constants::USER_AGENT;
+use crate::api::{Api, ApiResponse, Method, MonitorStatus};
+use crate::constants::{
+    DEFAULT_INITIAL_INTERVAL, DEFAULT_SEND_TIMEOUT, MAX_EVENT_TAGS, MAX_EXTRA_SIZE,
+    MAX_TAG_KEY_LENGTH, MAX_TAG_VALUE_LENGTH, USER_AGENT,
+};
+use crate::utils::json_schema;
+use crate::utils::retry::{get_default_backoff, DurationAsMilliseconds};
 
 lazy_static! {
     static ref COMPONENT_RE: Regex = Regex::new(r#"^([^:]+): (.*)$"#).unwrap();
+    static ref AUTH_KEY_RE: Regex = Regex::new(r#"(sentry_(?:key|secret)=)[^,\s]+"#).unwrap();
+}
+
+/// The number of breadcrumbs kept from a logfile by default.
+pub const DEFAULT_LOGFILE_LIMIT: usize = 100;
+
+/// Controls how breadcrumbs are extracted from a logfile.
+pub struct LogfileOptions {
+    /// Parses off a leading `category: ` from each record.
+    pub with_component: bool,
+    /// Levels of breadcrumbs by category when parsing off categories.
+    /// Other categories are parsed as log level, eg: `WARN`.
+    pub category_levels: HashMap<String, Level>,
+    /// A custom pattern with `level` and `message` capture groups.
+    pub category_pattern: Option<Regex>,
+    /// Only keep the last `limit` breadcrumbs, `0` keeps all.
+    pub limit: usize,
+    /// Drops records with a timestamp before this.  Records without a
+    /// timestamp are kept.
+    pub since: Option<DateTime<Utc>>,
+}
+
+impl Default for LogfileOptions {
+    fn default() -> Self {
+        LogfileOptions {
+            with_component: false,
+            category_levels: HashMap::new(),
+            category_pattern: None,
+            limit: DEFAULT_LOGFILE_LIMIT,
+            since: None,
+        }
+    }
+}
+
+/// Compiles a pattern for `LogfileOptions::category_pattern` and checks
+/// that it contains the required capture groups.
+pub fn parse_category_pattern(pattern: &str) -> Result<Regex, Error> {
+    let re = Regex::new(pattern).context("Invalid category pattern")?;
+    for group in &["level", "message"] {
+        if !re.capture_names().any(|name| name == Some(group)) {
+            bail!(
+                "Category pattern is missing the named capture group '{}'",
+                group
+            );
+        }
+    }
+    Ok(re)
+}
+
+/// Maps a log level as commonly found in logfiles to a sentry level.
+fn parse_log_level(level: &str) -> Level {
+    match level.to_lowercase().as_str() {
+        "trace" => Level::Debug,
+        "warn" => Level::Warning,
+        "err" => Level::Error,
+        "crit" | "critical" => Level::Fatal,
+        other => other.parse().unwrap_or(Level::Info),
+    }
 }
 
 /// Attaches all logs from a logfile as breadcrumbs to the given event.
+///
+/// The limit applies to all breadcrumbs of the event, so breadcrumbs of
+/// previously attached logfiles count towards it.
 pub fn attach_logfile(
     event: &mut Event<'_>,
     logfile: &str,
-    with_component: bool,
+    options: &LogfileOptions,
 ) -> Result<(), Error> {
     let f = fs::File::open(logfile).context("Could not open logfile")?;
 
@@ -35,30 +119,70 @@ pub fn attach_logfile(
         .map(Into::into)
         .unwrap_or_else(|_| Utc::now());
 
+    let mut breadcrumbs = vec![];
     let reader = BufReader::new(f);
     for line in reader.lines() {
         let line = line?;
         let rec = anylog::LogEntry::parse(line.as_bytes());
+        if matches!((options.since, rec.utc_timestamp()), (Some(since), Some(ts)) if ts < since) {
+            continue;
+        }
+        let timestamp = rec.utc_timestamp().unwrap_or(fallback_timestamp);
+
+        let captures = options
+            .category_pattern
+            .as_ref()
+            .and_then(|re| re.captures(rec.message()));
 
-        let (component, message) = if with_component {
-            let (component, message) = rec.component_and_message();
-            (component.unwrap_or("log"), message)
+        let breadcrumb = if let Some(captures) = captures {
+            Breadcrumb {
+                timestamp,
+                message: captures.name("message").map(|m| m.as_str().to_string()),
+                category: Some(
+                    captures
+                        .name("category")
+                        .map_or("log", |m| m.as_str())
+                        .to_string(),
+                ),
+                level: captures
+                    .name("level")
+                    .map_or(Level::Info, |m| parse_log_level(m.as_str())),
+                ..Default::default()
+            }
         } else {
-            ("log", rec.message())
+            let (component, message, level) = if options.with_component {
+                let (component, message) = rec.component_and_message();
+                let level = match component {
+                    Some(component) => match options.category_levels.get(component) {
+                        Some(level) => *level,
+                        None => parse_log_level(component),
+                    },
+                    None => Level::Info,
+                };
+                (component.unwrap_or("log"), message, level)
+            } else {
+                ("log", rec.message(), Level::Info)
+            };
+
+            Breadcrumb {
+                timestamp,
+                message: Some(message.to_string()),
+                category: Some(component.to_string()),
+                level,
+                ..Default::default()
+            }
         };
 
-        event.breadcrumbs.values.push(Breadcrumb {
-            timestamp: rec.utc_timestamp().unwrap_or(fallback_timestamp),
-            message: Some(message.to_string()),
-            category: Some(component.to_string()),
-            ..Default::default()
-        })
+        breadcrumbs.push(breadcrumb);
     }
 
-    if event.breadcrumbs.len() > 100 {
-        let skip = event.breadcrumbs.len() - 100;
-        event.breadcrumbs.values.drain(..skip);
+    // the limit applies to every logfile on its own, the combined number of
+    // breadcrumbs is capped separately
+    if options.limit > 0 && breadcrumbs.len() > options.limit {
+        let skip = breadcrumbs.len() - options.limit;
+        breadcrumbs.drain(..skip);
     }
+    event.breadcrumbs.values.extend(breadcrumbs);
 
     Ok(())
 }
@@ -73,6 +197,65 @@ pub fn get_sdk_info() -> Cow<'static, ClientSdkInfo> {
     })
 }
 
+/// Returns contexts describing the host operating system and sentry-cli as
+/// the runtime that produced the event.
+pub fn get_default_contexts() -> Map<String, Context> {
+    let sdk_info = get_sdk_info();
+    let mut contexts = Map::new();
+    contexts.insert("os".into(), Context::Os(Box::new(get_os_context())));
+    contexts.insert(
+        "runtime".into(),
+        Context::Runtime(Box::new(RuntimeContext {
+            name: Some(sdk_info.name.clone()),
+            version: Some(sdk_info.version.clone()),
+            ..Default::default()
+        })),
+    );
+    contexts
+}
+
+fn get_os_context() -> OsContext {
+    let mut os = OsContext {
+        name: Some(env::consts::OS.into()),
+        kernel_version: get_kernel_version(),
+        ..Default::default()
+    };
+
+    // on linux the distribution information is more useful than the generic
+    // os name as the kernel version is reported separately.
+    if let Ok(os_release) = fs::read_to_string("/etc/os-release") {
+        for line in os_release.lines() {
+            let mut split = line.splitn(2, '=');
+            let key = split.next().unwrap_or_default();
+            let value = split.next().unwrap_or_default().trim_matches('"');
+            match key {
+                "NAME" => os.name = Some(value.into()),
+                "VERSION_ID" => os.version = Some(value.into()),
+                _ => {}
+            }
+        }
+    }
+
+    os
+}
+
+#[cfg(unix)]
+fn get_kernel_version() -> Option<String> {
+    use std::ffi::CStr;
+
+    let mut info: libc::utsname = unsafe { std::mem::zeroed() };
+    if unsafe { libc::uname(&mut info) } != 0 {
+        return None;
+    }
+    let release = unsafe { CStr::from_ptr(info.release.as_ptr()) };
+    Some(release.to_string_lossy().into_owned())
+}
+
+#[cfg(not(unix))]
+fn get_kernel_version() -> Option<String> {
+    None
+}
+
 /// Executes the callback with an isolate sentry client on an empty isolate scope.
 ///
 /// Use the client's API to capture exceptions or manual events. The client will automatically drop
@@ -94,3 +277,623 @@ where
     client.close(Some(Duration::from_secs(2)));
     rv
 }
+
+/// Controls the delivery of envelopes in [`send_envelope`].
+#[derive(Clone, Debug)]
+pub struct SendOptions {
+    /// The number of times a transient failure is retried.
+    pub retries: u32,
+    /// The delay before the first retry.  Subsequent retries back off exponentially.
+    pub retry_delay: Duration,
+    /// The maximum time to spend sending, including all retries.
+    pub timeout: Duration,
+    /// Sends to this Relay or proxy instead of the host of the DSN.
+    pub relay_url: Option<Url>,
+    /// Sends envelopes with gzip content encoding.
+    pub compress: bool,
+    /// The maximum time to wait for queued events when closing the client.
+    /// Waits until all events are sent if not set.
+    pub flush_timeout: Option<Duration>,
+}
+
+impl Default for SendOptions {
+    fn default() -> SendOptions {
+        SendOptions {
+            retries: 3,
+            retry_delay: Duration::from_millis(DEFAULT_INITIAL_INTERVAL),
+            timeout: Duration::from_secs(DEFAULT_SEND_TIMEOUT),
+            relay_url: None,
+            compress: false,
+            flush_timeout: None,
+        }
+    }
+}
+
+/// Exit code when an event was rejected by the server.
+pub const EXIT_REJECTED: i32 = 1;
+/// Exit code when an event was rate limited by the server.
+pub const EXIT_RATE_LIMITED: i32 = 2;
+/// Exit code for network errors, server errors and timeouts.
+pub const EXIT_NETWORK: i32 = 3;
+/// Exit code when an event could not be read or failed validation.
+pub const EXIT_INVALID_INPUT: i32 = 4;
+/// Exit code when the DSN is missing or invalid or the server denied access.
+pub const EXIT_AUTH: i32 = 5;
+/// Exit code when an event was queued but not confirmed before the flush timeout.
+pub const EXIT_UNCONFIRMED: i32 = 6;
+/// Exit code when the deadline expired before all event files were sent.
+pub const EXIT_DEADLINE: i32 = 7;
+
+/// The reason why an event could not be delivered.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+pub enum SendErrorKind {
+    #[fail(display = "rate limited by the server")]
+    RateLimited,
+    #[fail(display = "network or server error")]
+    Network,
+    #[fail(display = "rejected by the server")]
+    Rejected,
+    #[fail(display = "access denied by the server")]
+    Unauthorized,
+    #[fail(display = "timed out")]
+    TimedOut,
+    #[fail(display = "not confirmed before the flush timeout")]
+    Unconfirmed,
+}
+
+impl SendErrorKind {
+    /// The exit code used when an event could not be delivered.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            SendErrorKind::Rejected => EXIT_REJECTED,
+            SendErrorKind::RateLimited => EXIT_RATE_LIMITED,
+            SendErrorKind::Network | SendErrorKind::TimedOut => EXIT_NETWORK,
+            SendErrorKind::Unauthorized => EXIT_AUTH,
+            SendErrorKind::Unconfirmed => EXIT_UNCONFIRMED,
+        }
+    }
+}
+
+/// Error returned when an event could not be delivered.
+#[derive(Debug, Fail)]
+#[fail(
+    display = "Failed to send event after {} attempt(s), {}",
+    attempts, kind
+)]
+pub struct SendError {
+    pub kind: SendErrorKind,
+    pub attempts: u32,
+}
+
+/// Returns the delivery failure that caused the given error, if any.
+pub fn get_send_error(err: &Error) -> Option<&SendError> {
+    err.iter_chain()
+        .filter_map(|cause| cause.downcast_ref::<failure::Context<SendError>>())
+        .map(failure::Context::get_context)
+        .next()
+}
+
+/// Returns whether the request failed because it ran into its timeout.
+fn is_timeout(err: &Error) -> bool {
+    err.iter_chain()
+        .filter_map(|cause| cause.downcast_ref::<curl::Error>())
+        .any(curl::Error::is_operation_timedout)
+}
+
+/// Returns whether a failed request with the given status is worth retrying.
+fn is_transient_status(status: u32) -> bool {
+    status == 429 || status >= 500
+}
+
+/// Sends an envelope to Sentry and waits for it to be accepted.
+///
+/// Network errors, rate limits and server errors are retried with exponential backoff as
+/// configured in the given options.  Other failures are returned immediately.  The time
+/// spent on all attempts together is bounded by the timeout of the options.
+pub fn send_envelope(dsn: &Dsn, envelope: &Envelope, options: &SendOptions) -> Result<(), Error> {
+    send_raw_envelope(dsn, serialize_envelope(envelope)?, options)
+}
+
+/// A check-in of a cron monitor.
+#[derive(Debug, Serialize)]
+pub struct CheckIn {
+    pub check_in_id: Uuid,
+    pub monitor_slug: String,
+    pub status: MonitorStatus,
+    /// The duration of the job in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+}
+
+/// Sends a cron monitor check-in to the envelope endpoint of the DSN.
+pub fn send_check_in(dsn: &Dsn, check_in: &CheckIn, options: &SendOptions) -> Result<(), Error> {
+    let payload = serde_json::to_vec(check_in)?;
+    let item_header = serde_json::json!({
+        "type": "check_in",
+        "length": payload.len(),
+    });
+
+    let mut body = b"{}\n".to_vec();
+    serde_json::to_writer(&mut body, &item_header)?;
+    body.push(b'\n');
+    body.extend(payload);
+    body.push(b'\n');
+    send_raw_envelope(dsn, body, options)
+}
+
+/// Checks the tags and extra data of an event against the limits of Sentry.
+/// Violations are logged as warnings, or returned as error if `strict`.
+pub fn check_event_limits(event: &Event<'_>, strict: bool) -> Result<(), Error> {
+    let mut problems = vec![];
+
+    if event.tags.len() > MAX_EVENT_TAGS {
+        problems.push(format!(
+            "event has {} tags (limit is {})",
+            event.tags.len(),
+            MAX_EVENT_TAGS
+        ));
+    }
+
+    for (key, value) in &event.tags {
+        if key.chars().count() > MAX_TAG_KEY_LENGTH {
+            problems.push(format!(
+                "tag key '{}' is longer than {} characters",
+                key, MAX_TAG_KEY_LENGTH
+            ));
+        }
+        if value.chars().count() > MAX_TAG_VALUE_LENGTH {
+            problems.push(format!(
+                "value of tag '{}' is longer than {} characters",
+                key, MAX_TAG_VALUE_LENGTH
+            ));
+        }
+    }
+
+    let extra_size = serde_json::to_vec(&event.extra)?.len();
+    if extra_size > MAX_EXTRA_SIZE {
+        problems.push(format!(
+            "extra data is {} (limit is {})",
+            HumanBytes(extra_size as u64),
+            HumanBytes(MAX_EXTRA_SIZE as u64)
+        ));
+    }
+
+    if strict && !problems.is_empty() {
+        bail!("Event exceeds Sentry limits: {}", problems.join(", "));
+    }
+    for problem in problems {
+        warn!("Event exceeds Sentry limits: {}", problem);
+    }
+
+    Ok(())
+}
+
+/// Reads a JSON schema that events are validated against.
+pub fn read_schema(path: &Path) -> Result<Value, Error> {
+    let contents =
+        fs::read(path).with_context(|_| format!("Could not read schema {}", path.display()))?;
+    let schema = serde_json::from_slice(&contents)
+        .with_context(|_| format!("Invalid JSON schema {}", path.display()))?;
+    let unsupported = json_schema::find_unsupported_keywords(&schema);
+    if !unsupported.is_empty() {
+        bail!(
+            "Schema {} uses unsupported keywords: {}",
+            path.display(),
+            unsupported.join(", ")
+        );
+    }
+    Ok(schema)
+}
+
+/// Validates the event payload against a JSON schema.
+pub fn check_event_schema(event: &Event<'_>, schema: &Value) -> Result<(), Error> {
+    let errors = json_schema::validate(schema, &serde_json::to_value(event)?);
+    if !errors.is_empty() {
+        bail!(
+            "Event {} does not match the schema: {}",
+            event.event_id,
+            errors.iter().join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Fixes up a serialized event or transaction for what Sentry expects.
+///
+/// The protocol types serialize span ids as full UUIDs while Sentry requires
+/// 16 hex characters, and emit the name of transactions as `name` instead of
+/// `transaction`.
+pub fn normalize_payload(payload: &mut Value, transaction: bool) {
+    let truncate = |id: Option<&mut Value>| {
+        if let Some(Value::String(id)) = id {
+            id.truncate(16);
+        }
+    };
+
+    truncate(payload.pointer_mut("/contexts/trace/span_id"));
+    if let Some(Value::Array(spans)) = payload.pointer_mut("/spans") {
+        for span in spans {
+            truncate(span.get_mut("span_id"));
+        }
+    }
+
+    if let (true, Value::Object(payload)) = (transaction, payload) {
+        if let Some(name) = payload.remove("name") {
+            payload.insert("transaction".into(), name);
+        }
+        payload.insert("type".into(), "transaction".into());
+    }
+}
+
+fn serialize_payload<T: Serialize>(item: &T, transaction: bool) -> Result<Vec<u8>, Error> {
+    let mut payload = serde_json::to_value(item)?;
+    normalize_payload(&mut payload, transaction);
+    Ok(serde_json::to_vec(&payload)?)
+}
+
+/// Serializes an envelope the same way as `Envelope::to_writer`, but with
+/// normalized event and transaction payloads.
+pub fn serialize_envelope(envelope: &Envelope) -> Result<Vec<u8>, Error> {
+    let mut rv = vec![];
+    match envelope.uuid() {
+        Some(id) => writeln!(rv, r#"{{"event_id":"{}"}}"#, id)?,
+        None => writeln!(rv, "{{}}")?,
+    }
+
+    for item in envelope.items() {
+        let (ty, payload) = match item {
+            EnvelopeItem::Event(event) => ("event", serialize_payload(event, false)?),
+            EnvelopeItem::Transaction(transaction) => {
+                ("transaction", serialize_payload(transaction, true)?)
+            }
+            EnvelopeItem::SessionUpdate(session) => ("session", serde_json::to_vec(session)?),
+            EnvelopeItem::SessionAggregates(aggregates) => {
+                ("sessions", serde_json::to_vec(aggregates)?)
+            }
+            EnvelopeItem::Attachment(attachment) => {
+                attachment.to_writer(&mut rv)?;
+                writeln!(rv)?;
+                continue;
+            }
+            _ => bail!("Unsupported envelope item"),
+        };
+        writeln!(rv, r#"{{"type":"{}","length":{}}}"#, ty, payload.len())?;
+        rv.extend(payload);
+        writeln!(rv)?;
+    }
+
+    Ok(rv)
+}
+
+/// Returns the envelope endpoint for the DSN, on the Relay if one is configured.
+///
+/// The DSN still provides the project and the public key for authentication.
+fn get_envelope_url(dsn: &Dsn, options: &SendOptions) -> String {
+    match options.relay_url {
+        Some(ref relay_url) => format!(
+            "{}/api/{}/envelope/",
+            relay_url.as_str().trim_end_matches('/'),
+            dsn.project_id()
+        ),
+        None => dsn.envelope_api_url().to_string(),
+    }
+}
+
+/// Sends an already serialized envelope to the envelope endpoint of the DSN.
+///
+/// This retries the same way as `send_envelope`.
+pub fn send_raw_envelope(dsn: &Dsn, body: Vec<u8>, options: &SendOptions) -> Result<(), Error> {
+    let url = get_envelope_url(dsn, options);
+    let auth = dsn.to_auth(Some(USER_AGENT)).to_string();
+    let (body, encoding) = if options.compress {
+        match gzip(&body) {
+            Ok(compressed) => (compressed, Some("gzip")),
+            Err(err) => {
+                warn!("Failed to compress event, sending it uncompressed: {}", err);
+                (body, None)
+            }
+        }
+    } else {
+        (body, None)
+    };
+
+    let mut backoff = get_default_backoff();
+    backoff.initial_interval = options.retry_delay;
+    backoff.current_interval = options.retry_delay;
+
+    let deadline = Instant::now() + options.timeout;
+    let mut attempt = 0;
+    loop {
+        // curl rounds the timeout down to milliseconds and treats 0 as no
+        // timeout, so the last attempt must not get less than that
+        let remaining = deadline
+            .saturating_duration_since(Instant::now())
+            .max(Duration::from_millis(1));
+        let rv = Api::current()
+            .request(Method::Post, &url)
+            .and_then(|req| req.with_header("X-Sentry-Auth", &auth))
+            .and_then(|req| req.with_header("Content-Type", "application/x-sentry-envelope"))
+            .and_then(|req| match encoding {
+                Some(encoding) => req.with_header("Content-Encoding", encoding),
+                None => Ok(req),
+            })
+            .and_then(|req| req.with_timeout(remaining))
+            .and_then(|req| req.with_body(body.clone()))
+            .and_then(|req| req.send());
+
+        let (kind, err): (SendErrorKind, Error) = match rv {
+            Ok(resp) => {
+                let status = resp.status();
+                match resp.into_result() {
+                    Ok(_) => return Ok(()),
+                    Err(err) => (get_status_error_kind(status), err.into()),
+                }
+            }
+            Err(err) => {
+                let err = Error::from(err);
+                if is_timeout(&err) {
+                    (SendErrorKind::TimedOut, err)
+                } else {
+                    (SendErrorKind::Network, err)
+                }
+            }
+        };
+
+        let delay = backoff.next_backoff().unwrap_or(options.retry_delay);
+        let kind = match kind {
+            SendErrorKind::Rejected | SendErrorKind::Unauthorized | SendErrorKind::TimedOut => {
+                Some(kind)
+            }
+            _ if attempt >= options.retries => Some(kind),
+            _ if Instant::now() + delay >= deadline => Some(SendErrorKind::TimedOut),
+            _ => None,
+        };
+
+        if let Some(kind) = kind {
+            return Err(err
+                .context(SendError {
+                    kind,
+                    attempts: attempt + 1,
+                })
+                .into());
+        }
+
+        warn!(
+            "Failed to send event, retrying in {}ms: {}",
+            delay.as_milliseconds(),
+            err
+        );
+        thread::sleep(delay);
+        attempt += 1;
+    }
+}
+
+/// Quotes a string for use as a single POSIX shell argument.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r#"'\''"#))
+}
+
+/// Returns a curl command that posts a serialized envelope the same way as
+/// `send_raw_envelope`, except for compression.
+///
+/// Unless `full` is set, the keys in the auth header are redacted.
+pub fn get_curl_command(dsn: &Dsn, body: &[u8], options: &SendOptions, full: bool) -> String {
+    let auth = dsn.to_auth(Some(USER_AGENT)).to_string();
+    let auth = if full {
+        auth
+    } else {
+        AUTH_KEY_RE.replace_all(&auth, "$1[Filtered]").into_owned()
+    };
+
+    format!(
+        "curl -X POST {} \\\n  -H {} \\\n  -H {} \\\n  --data-binary {}",
+        shell_quote(&get_envelope_url(dsn, options)),
+        shell_quote(&format!("X-Sentry-Auth: {}", auth)),
+        shell_quote("Content-Type: application/x-sentry-envelope"),
+        shell_quote(&String::from_utf8_lossy(body)),
+    )
+}
+
+/// Returns why an event was not delivered if the server responded with a
+/// failure status.
+pub fn get_status_error_kind(status: u32) -> SendErrorKind {
+    match status {
+        429 => SendErrorKind::RateLimited,
+        401 | 403 => SendErrorKind::Unauthorized,
+        status if is_transient_status(status) => SendErrorKind::Network,
+        _ => SendErrorKind::Rejected,
+    }
+}
+
+/// Sends an empty envelope to the envelope endpoint of the DSN to check that
+/// it accepts events, without retries.
+///
+/// Failed requests are returned as response, only network errors fail.
+pub fn check_envelope_endpoint(dsn: &Dsn, options: &SendOptions) -> Result<ApiResponse, Error> {
+    let url = get_envelope_url(dsn, options);
+    let auth = dsn.to_auth(Some(USER_AGENT)).to_string();
+    let rv = Api::current()
+        .request(Method::Post, &url)
+        .and_then(|req| req.with_header("X-Sentry-Auth", &auth))
+        .and_then(|req| req.with_header("Content-Type", "application/x-sentry-envelope"))
+        .and_then(|req| req.with_timeout(options.timeout))
+        .and_then(|req| req.with_body(b"{}\n".to_vec()))
+        .and_then(|req| req.send());
+
+    rv.map_err(|err| {
+        let err = Error::from(err);
+        let kind = if is_timeout(&err) {
+            SendErrorKind::TimedOut
+        } else {
+            SendErrorKind::Network
+        };
+        err.context(SendError { kind, attempts: 1 }).into()
+    })
+}
+
+/// Compresses a serialized envelope with gzip.
+fn gzip(data: &[u8]) -> Result<Vec<u8>, io::Error> {
+    let mut encoder = GzEncoder::new(Vec::new(), Default::default());
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+/// Splits off the first line of a buffer.
+fn split_line(data: &[u8]) -> (&[u8], &[u8]) {
+    match data.iter().position(|&b| b == b'\n') {
+        Some(pos) => (&data[..pos], &data[pos + 1..]),
+        None => (data, &[]),
+    }
+}
+
+/// Checks the structure of a serialized envelope and returns the ids of the
+/// events and transactions it contains.
+pub fn get_envelope_event_ids(data: &[u8]) -> Result<Vec<Uuid>, Error> {
+    let (header, mut rest) = split_line(data);
+    let header: Value = serde_json::from_slice(header).context("Invalid envelope header")?;
+
+    let mut ids = vec![];
+    if let Some(id) = header.get("event_id").and_then(Value::as_str) {
+        ids.push(
+            id.parse::<Uuid>()
+                .context("Invalid event id in envelope header")?,
+        );
+    }
+
+    while !rest.is_empty() {
+        let (item_header, after_header) = split_line(rest);
+        if item_header.is_empty() {
+            rest = after_header;
+            continue;
+        }
+
+        let item: Value =
+            serde_json::from_slice(item_header).context("Invalid envelope item header")?;
+        let payload = match item.get("length").and_then(Value::as_u64) {
+            Some(length) => {
+                let length = length as usize;
+                if after_header.len() < length {
+                    bail!("Envelope item is truncated");
+                }
+                rest = &after_header[length..];
+                &after_header[..length]
+            }
+            None => {
+                let (payload, after_payload) = split_line(after_header);
+                rest = after_payload;
+                payload
+            }
+        };
+
+        let ty = item.get("type").and_then(Value::as_str);
+        if ids.is_empty() && (ty == Some("event") || ty == Some("transaction")) {
+            let payload: Value =
+                serde_json::from_slice(payload).context("Invalid envelope item payload")?;
+            if let Some(id) = payload.get("event_id").and_then(Value::as_str) {
+                ids.push(
+                    id.parse::<Uuid>()
+                        .context("Invalid event id in envelope item")?,
+                );
+            }
+        }
+    }
+
+    Ok(ids)
+}
+
+/// A transport that sends envelopes in the background and records delivery failures.
+struct CheckedTransport {
+    dsn: Dsn,
+    options: SendOptions,
+    errors: Arc<Mutex<Vec<Error>>>,
+    pending: Arc<(Mutex<usize>, Condvar)>,
+}
+
+impl Transport for CheckedTransport {
+    fn send_envelope(&self, envelope: Envelope) {
+        *self.pending.0.lock() += 1;
+        let dsn = self.dsn.clone();
+        let options = self.options.clone();
+        let errors = self.errors.clone();
+        let pending = self.pending.clone();
+        thread::spawn(move || {
+            if let Err(err) = send_envelope(&dsn, &envelope, &options) {
+                errors.lock().push(err);
+            }
+            let (count, done) = &*pending;
+            *count.lock() -= 1;
+            done.notify_all();
+        });
+    }
+
+    fn flush(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now().checked_add(timeout);
+        let (count, done) = &*self.pending;
+        let mut count = count.lock();
+        while *count > 0 {
+            match deadline {
+                Some(deadline) => {
+                    if done.wait_until(&mut count, deadline).timed_out() {
+                        return *count == 0;
+                    }
+                }
+                None => done.wait(&mut count),
+            }
+        }
+        true
+    }
+}
+
+/// Like [`with_sentry_client`] but waits for all events and fails if any of them could not
+/// be delivered.
+///
+/// Transient failures are retried according to the given options.  If the events are not
+/// confirmed within the flush timeout of the options, this fails with
+/// [`SendErrorKind::Unconfirmed`] although the events may still arrive.
+///
+/// Send events with `Client::send_envelope` so that they go out unchanged.
+/// `Client::capture_event` still fills in fields such as the platform.
+pub fn with_checked_sentry_client<F, R>(
+    dsn: Dsn,
+    options: &SendOptions,
+    callback: F,
+) -> Result<R, Error>
+where
+    F: FnOnce(&Client) -> R,
+{
+    let transport = Arc::new(CheckedTransport {
+        dsn: dsn.clone(),
+        options: options.clone(),
+        errors: Arc::new(Mutex::new(Vec::new())),
+        pending: Arc::new((Mutex::new(0), Condvar::new())),
+    });
+    // no defaults, the client must not fill in release or environment from
+    // the process environment
+    let client = Client::from_config((
+        dsn,
+        ClientOptions {
+            user_agent: USER_AGENT.into(),
+            transport: Some(Arc::new(transport.clone())),
+            ..Default::default()
+        },
+    ));
+
+    let rv = callback(&client);
+    if !client.close(Some(options.flush_timeout.unwrap_or(Duration::MAX))) {
+        warn!("Events were queued but not confirmed, they may not have been delivered");
+        return Err(failure::err_msg("Timed out waiting for events to be sent")
+            .context(SendError {
+                kind: SendErrorKind::Unconfirmed,
+                attempts: 1,
+            })
+            .into());
+    }
+
+    let err = transport.errors.lock().pop();
+    match err {
+        Some(err) => Err(err),
+        None => Ok(rv),
+    }
+}
@@ -0,0 +1,166 @@
+//! Reading of event files in JSON and YAML format.
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use clap::ArgMatches;
+use failure::{Error, Fail, ResultExt};
+use flate2::read::GzDecoder;
+use glob::Pattern;
+use sentry::protocol::Event;
+use serde::Deserialize;
+use serde_json::Deserializer;
+
+/// Magic bytes at the start of gzip compressed files.
+const GZIP_MAGIC: &[u8] = b"\x1f\x8b";
+
+/// The format of event files.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FileFormat {
+    Json,
+    Yaml,
+}
+
+impl FileFormat {
+    /// Returns the format forced with the `format` argument, if any.
+    pub fn from_matches(matches: &ArgMatches<'_>) -> Option<FileFormat> {
+        matches.value_of("format").map(|format| match format {
+            "yaml" => FileFormat::Yaml,
+            _ => FileFormat::Json,
+        })
+    }
+}
+
+/// Returns the glob pattern for the given path argument.
+///
+/// Directories are expanded to all JSON files they contain.
+pub fn get_path_pattern(path: &str, recursive: bool) -> Cow<'_, str> {
+    if !Path::new(path).is_dir() {
+        return Cow::Borrowed(path);
+    }
+
+    let mut pattern = PathBuf::from(Pattern::escape(path));
+    if recursive {
+        pattern.push("**");
+    }
+    pattern.push("*.json");
+    Cow::Owned(pattern.to_string_lossy().into_owned())
+}
+
+/// Reads all events from a JSON or YAML event file.
+pub fn read_events(
+    path: &Path,
+    ndjson: bool,
+    format: Option<FileFormat>,
+) -> Result<Vec<Event<'static>>, Error> {
+    if is_yaml_file(path, format) {
+        read_yaml_events(path)
+    } else {
+        read_events_from_file(path, ndjson)
+    }
+}
+
+/// Reads all events from a file containing a single JSON event, concatenated
+/// JSON events or newline-delimited JSON events.
+fn read_events_from_file(path: &Path, ndjson: bool) -> Result<Vec<Event<'static>>, Error> {
+    parse_events(&String::from_utf8(read_event_file(path)?)?, ndjson)
+}
+
+/// Parses events like `read_events_from_file` from the contents of a file.
+pub fn parse_events(contents: &str, ndjson: bool) -> Result<Vec<Event<'static>>, Error> {
+    if ndjson {
+        return parse_ndjson_events(contents);
+    }
+
+    match serde_json::from_str(contents) {
+        Ok(event) => Ok(vec![event]),
+        // the file might contain several events back-to-back.  Otherwise,
+        // fall back to newline-delimited JSON but report the original error
+        // if that does not work out either.
+        Err(err) => match parse_concatenated_events(contents) {
+            Some(events) => events,
+            None => parse_ndjson_events(contents).map_err(|_| err.into()),
+        },
+    }
+}
+
+/// Parses events from JSON objects that follow each other without
+/// delimiters.  Returns `None` if the contents do not start with an event.
+fn parse_concatenated_events(contents: &str) -> Option<Result<Vec<Event<'static>>, Error>> {
+    let mut stream = Deserializer::from_reader(contents.as_bytes()).into_iter::<Event<'static>>();
+    let mut events = vec![stream.next()?.ok()?];
+    while let Some(result) = stream.next() {
+        match result {
+            Ok(event) => events.push(event),
+            Err(err) if err.is_syntax() || err.is_eof() => {
+                return Some(Err(err
+                    .context(format!(
+                        "Unexpected trailing data after {} event(s) at byte {}",
+                        events.len(),
+                        stream.byte_offset()
+                    ))
+                    .into()));
+            }
+            Err(err) => {
+                return Some(Err(err
+                    .context(format!("Invalid event {}", events.len() + 1))
+                    .into()));
+            }
+        }
+    }
+    Some(Ok(events))
+}
+
+/// Reads the contents of an event file, transparently decompressing gzip
+/// files.
+pub fn read_event_file(path: &Path) -> Result<Vec<u8>, Error> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut contents = vec![];
+    if reader.fill_buf()?.starts_with(GZIP_MAGIC) {
+        GzDecoder::new(reader)
+            .read_to_end(&mut contents)
+            .with_context(|_| format!("Could not decompress {}", path.display()))?;
+    } else {
+        reader.read_to_end(&mut contents)?;
+    }
+    Ok(contents)
+}
+
+/// Returns whether the file at the path is read as YAML.
+fn is_yaml_file(path: &Path, format: Option<FileFormat>) -> bool {
+    match format {
+        Some(format) => format == FileFormat::Yaml,
+        None => matches!(
+            path.extension().and_then(OsStr::to_str),
+            Some("yaml") | Some("yml")
+        ),
+    }
+}
+
+/// Reads all events from a YAML file, one per document.
+fn read_yaml_events(path: &Path) -> Result<Vec<Event<'static>>, Error> {
+    let contents = read_event_file(path)?;
+    serde_yaml::Deserializer::from_slice(&contents)
+        .enumerate()
+        .map(|(idx, document)| {
+            Ok(Event::deserialize(document)
+                .with_context(|_| format!("Invalid event in YAML document {}", idx + 1))?)
+        })
+        .collect()
+}
+
+/// Parses events from newline-delimited JSON.
+fn parse_ndjson_events(contents: &str) -> Result<Vec<Event<'static>>, Error> {
+    let mut events = vec![];
+    for (idx, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event = serde_json::from_str(line)
+            .with_context(|_| format!("Invalid event on line {}", idx + 1))?;
+        events.push(event);
+    }
+    Ok(events)
+}
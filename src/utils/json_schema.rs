@@ -0,0 +1,324 @@
+//! Validation of JSON values against a JSON Schema.
+//!
+//! Only a subset of the specification is implemented: `type`, `enum`,
+//! `const`, `required`, `properties`, `additionalProperties`, `items`,
+//! `minItems`, `maxItems`, `minLength`, `maxLength`, `minimum`, `maximum`,
+//! `pattern`, `allOf`, `anyOf` and boolean schemas.  Annotations such as
+//! `title` or `description` are ignored, schemas using other keywords are
+//! rejected by [`find_unsupported_keywords`].
+use std::fmt;
+
+use regex::Regex;
+use serde_json::Value;
+
+/// A value that does not match the schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaError {
+    /// The JSON pointer of the value.
+    pub pointer: String,
+    /// Why the value does not match.
+    pub message: String,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}: {}", self.pointer, self.message)
+    }
+}
+
+/// Validates a value against a schema and returns all violations.
+pub fn validate(schema: &Value, value: &Value) -> Vec<SchemaError> {
+    let mut errors = vec![];
+    validate_at(schema, value, "", &mut errors);
+    errors
+}
+
+/// Keywords checked by [`validate`].
+const SUPPORTED_KEYWORDS: &[&str] = &[
+    "type",
+    "enum",
+    "const",
+    "required",
+    "properties",
+    "additionalProperties",
+    "items",
+    "minItems",
+    "maxItems",
+    "minLength",
+    "maxLength",
+    "minimum",
+    "maximum",
+    "pattern",
+    "allOf",
+    "anyOf",
+];
+
+/// Keywords that only describe a schema and do not affect validation.
+const ANNOTATION_KEYWORDS: &[&str] = &[
+    "$schema",
+    "$id",
+    "$comment",
+    "title",
+    "description",
+    "default",
+    "examples",
+    "readOnly",
+    "writeOnly",
+    "deprecated",
+];
+
+/// Returns the keywords of a schema that [`validate`] does not check, as JSON
+/// pointers into the schema.  Values would silently pass these keywords.
+pub fn find_unsupported_keywords(schema: &Value) -> Vec<String> {
+    let mut rv = vec![];
+    find_unsupported_at(schema, "", &mut rv);
+    rv
+}
+
+fn find_unsupported_at(schema: &Value, pointer: &str, rv: &mut Vec<String>) {
+    let schema = match schema {
+        Value::Object(schema) => schema,
+        _ => return,
+    };
+
+    for (keyword, value) in schema {
+        let child_pointer = format!("{}/{}", pointer, escape_pointer(keyword));
+        if ANNOTATION_KEYWORDS.contains(&keyword.as_str()) {
+            continue;
+        }
+        if !SUPPORTED_KEYWORDS.contains(&keyword.as_str()) {
+            rv.push(format!("#{}", child_pointer));
+            continue;
+        }
+        match (keyword.as_str(), value) {
+            ("properties", Value::Object(properties)) => {
+                for (key, child) in properties {
+                    let pointer = format!("{}/{}", child_pointer, escape_pointer(key));
+                    find_unsupported_at(child, &pointer, rv);
+                }
+            }
+            ("allOf", Value::Array(schemas)) | ("anyOf", Value::Array(schemas)) => {
+                for (idx, child) in schemas.iter().enumerate() {
+                    find_unsupported_at(child, &format!("{}/{}", child_pointer, idx), rv);
+                }
+            }
+            // the tuple form of items is not supported
+            ("items", Value::Array(_)) => rv.push(format!("#{}", child_pointer)),
+            ("items", child) | ("additionalProperties", child) => {
+                find_unsupported_at(child, &child_pointer, rv)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn matches_type(ty: &str, value: &Value) -> bool {
+    match ty {
+        "integer" => {
+            value.is_i64()
+                || value.is_u64()
+                || matches!(value.as_f64(), Some(f) if f.fract() == 0.0)
+        }
+        ty => type_name(value) == ty,
+    }
+}
+
+fn report(errors: &mut Vec<SchemaError>, pointer: &str, message: String) {
+    errors.push(SchemaError {
+        pointer: pointer.to_owned(),
+        message,
+    });
+}
+
+fn validate_at(schema: &Value, value: &Value, pointer: &str, errors: &mut Vec<SchemaError>) {
+    let schema = match schema {
+        Value::Bool(true) => return,
+        Value::Bool(false) => return report(errors, pointer, "no value is allowed here".into()),
+        Value::Object(schema) => schema,
+        _ => return,
+    };
+
+    match schema.get("type") {
+        Some(Value::String(ty)) if !matches_type(ty, value) => {
+            report(
+                errors,
+                pointer,
+                format!("expected {}, found {}", ty, type_name(value)),
+            );
+            return;
+        }
+        Some(Value::Array(types)) => {
+            let types: Vec<_> = types.iter().filter_map(Value::as_str).collect();
+            if !types.iter().any(|ty| matches_type(ty, value)) {
+                report(
+                    errors,
+                    pointer,
+                    format!(
+                        "expected one of {}, found {}",
+                        types.join(", "),
+                        type_name(value)
+                    ),
+                );
+                return;
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(Value::Array(allowed)) = schema.get("enum") {
+        if !allowed.contains(value) {
+            report(
+                errors,
+                pointer,
+                format!("{} is not one of the allowed values", value),
+            );
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            report(
+                errors,
+                pointer,
+                format!("expected {}, found {}", expected, value),
+            );
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for key in required.iter().filter_map(Value::as_str) {
+                    if !object.contains_key(key) {
+                        report(
+                            errors,
+                            pointer,
+                            format!("missing required property '{}'", key),
+                        );
+                    }
+                }
+            }
+
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (key, child) in object {
+                let child_pointer = format!("{}/{}", pointer, escape_pointer(key));
+                match properties.and_then(|p| p.get(key)) {
+                    Some(child_schema) => validate_at(child_schema, child, &child_pointer, errors),
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => report(
+                            errors,
+                            &child_pointer,
+                            "additional property is not allowed".into(),
+                        ),
+                        Some(child_schema) => {
+                            validate_at(child_schema, child, &child_pointer, errors)
+                        }
+                        None => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if (items.len() as u64) < min {
+                    report(
+                        errors,
+                        pointer,
+                        format!("expected at least {} item(s)", min),
+                    );
+                }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                if items.len() as u64 > max {
+                    report(errors, pointer, format!("expected at most {} item(s)", max));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (idx, item) in items.iter().enumerate() {
+                    validate_at(item_schema, item, &format!("{}/{}", pointer, idx), errors);
+                }
+            }
+        }
+        Value::String(string) => {
+            let len = string.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if len < min {
+                    report(
+                        errors,
+                        pointer,
+                        format!("expected at least {} character(s)", min),
+                    );
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                if len > max {
+                    report(
+                        errors,
+                        pointer,
+                        format!("expected at most {} character(s)", max),
+                    );
+                }
+            }
+            if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+                match Regex::new(pattern) {
+                    Ok(regex) if !regex.is_match(string) => {
+                        report(
+                            errors,
+                            pointer,
+                            format!("does not match pattern '{}'", pattern),
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(_) => report(
+                        errors,
+                        pointer,
+                        format!("invalid pattern '{}' in schema", pattern),
+                    ),
+                }
+            }
+        }
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                if number < min {
+                    report(errors, pointer, format!("expected at least {}", min));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                if number > max {
+                    report(errors, pointer, format!("expected at most {}", max));
+                }
+            }
+        }
+        Value::Null | Value::Bool(_) => {}
+    }
+
+    if let Some(Value::Array(schemas)) = schema.get("allOf") {
+        for schema in schemas {
+            validate_at(schema, value, pointer, errors);
+        }
+    }
+    if let Some(Value::Array(schemas)) = schema.get("anyOf") {
+        if !schemas.iter().any(|s| validate(s, value).is_empty()) {
+            report(
+                errors,
+                pointer,
+                "does not match any of the allowed schemas".into(),
+            );
+        }
+    }
+}
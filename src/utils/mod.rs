@@ -9,17 +9,20 @@ pub mod dif;
 pub mod dif_upload;
 pub mod enc;
 pub mod event;
+pub mod event_file;
 pub mod file_search;
 pub mod file_upload;
 pub mod formatting;
 pub mod fs;
 pub mod http;
+pub mod json_schema;
 pub mod logging;
 pub mod progress;
 pub mod releases;
 pub mod retry;
 pub mod sourcemaps;
 pub mod system;
+pub mod tar;
 pub mod ui;
 pub mod update;
 pub mod vcs;
@@ -1,7 +1,7 @@
 use std::env;
 use std::ffi::OsStr;
 use std::fs;
-use std::io::Read;
+use std::io::{self, Read};
 use std::path::{Path, PathBuf};
 
 use failure::{bail, Error};
@@ -9,6 +9,9 @@ use if_chain::if_chain;
 use lazy_static::lazy_static;
 use regex::Regex;
 
+use crate::constants::{
+    INVALID_ENVIRONMENT_CHARS, MAX_ENVIRONMENT_LENGTH, RESERVED_ENVIRONMENT_NAME,
+};
 use crate::utils::cordova::CordovaConfig;
 use crate::utils::vcs;
 use crate::utils::xcode::InfoPlist;
@@ -83,8 +86,125 @@ pub fn infer_gradle_release_name(path: Option<PathBuf>) -> Result<Option<String>
     Ok(None)
 }
 
+/// Detects an environment name from `SENTRY_ENVIRONMENT` or the CI system the
+/// command runs on.
+///
+/// On CI this is the branch name where available, or `ci` on unknown CI
+/// systems and for builds of tags.
+pub fn detect_environment_name() -> Option<String> {
+    // an explicitly configured environment always wins over CI detection
+    if let Ok(environment) = env::var("SENTRY_ENVIRONMENT") {
+        if !environment.trim().is_empty() {
+            return Some(environment.trim().to_string());
+        }
+    }
+
+    // try GitHub Actions: https://docs.github.com/en/actions/reference/environment-variables
+    if let Ok(git_ref) = env::var("GITHUB_REF") {
+        // pull requests build refs/pull/<number>/merge, use their source branch
+        if let Ok(branch) = env::var("GITHUB_HEAD_REF") {
+            if !branch.is_empty() {
+                return Some(sanitize_environment_name(&branch));
+            }
+        }
+        if let Some(branch) = git_ref.strip_prefix("refs/heads/") {
+            if !branch.is_empty() {
+                return Some(sanitize_environment_name(branch));
+            }
+        }
+        if !git_ref.is_empty() {
+            return Some("ci".into());
+        }
+    }
+
+    // try GitLab CI, CircleCI, Travis CI, Bitbucket Pipelines and Buildkite
+    for var in &[
+        "CI_COMMIT_BRANCH",
+        "CIRCLE_BRANCH",
+        "TRAVIS_BRANCH",
+        "BITBUCKET_BRANCH",
+        "BUILDKITE_BRANCH",
+    ] {
+        if let Ok(branch) = env::var(var) {
+            if !branch.is_empty() {
+                return Some(sanitize_environment_name(&branch));
+            }
+        }
+    }
+
+    match env::var("CI") {
+        Ok(ci) if !ci.is_empty() && ci != "false" && ci != "0" => Some("ci".into()),
+        _ => None,
+    }
+}
+
+/// Checks that Sentry accepts the environment name and returns it without
+/// surrounding whitespace.
+pub fn validate_environment_name(name: &str) -> Result<&str, Error> {
+    let name = name.trim();
+    if name.is_empty() {
+        bail!("Environment name must not be empty");
+    }
+    if name.chars().count() > MAX_ENVIRONMENT_LENGTH {
+        bail!(
+            "Environment name {:?} is longer than {} characters",
+            name,
+            MAX_ENVIRONMENT_LENGTH
+        );
+    }
+    if name
+        .chars()
+        .any(|c| c.is_whitespace() || INVALID_ENVIRONMENT_CHARS.contains(&c))
+    {
+        bail!(
+            "Environment name {:?} must not contain whitespace or slashes",
+            name
+        );
+    }
+    if name == RESERVED_ENVIRONMENT_NAME {
+        bail!("Environment name {:?} is reserved", name);
+    }
+    Ok(name)
+}
+
+/// Sentry does not accept slashes or whitespace in environment names.
+fn sanitize_environment_name(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| {
+            if c == '/' || c.is_whitespace() {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Reads a release name from a file such as `VERSION`.  Returns `None` if the
+/// file does not exist or is empty.
+pub fn read_release_file(path: &Path) -> Result<Option<String>, Error> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents.trim().to_string()).filter(|r| !r.is_empty())),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(Error::from(err)
+            .context(format!("Could not read release file {}", path.display()))
+            .into()),
+    }
+}
+
 /// Detects the release name for the current working directory.
 pub fn detect_release_name() -> Result<String, Error> {
+    detect_release_name_with_fallback(None)
+}
+
+/// Detects the release name like [`detect_release_name`], but reads it from
+/// the given release file before falling back to the git HEAD.
+pub fn detect_release_name_with_file(path: &Path) -> Result<String, Error> {
+    detect_release_name_with_fallback(Some(path))
+}
+
+fn detect_release_name_with_fallback(release_file: Option<&Path>) -> Result<String, Error> {
     // cordova release detection first.
     if let Some(release) = get_cordova_release_name(None)? {
         return Ok(release);
@@ -135,6 +255,12 @@ pub fn detect_release_name() -> Result<String, Error> {
         return Ok(release);
     }
 
+    if let Some(path) = release_file {
+        if let Some(release) = read_release_file(path)? {
+            return Ok(release);
+        }
+    }
+
     if let Ok(head) = vcs::find_head() {
         Ok(head)
     } else {
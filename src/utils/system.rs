@@ -91,6 +91,25 @@ pub fn is_npm_install() -> bool {
     is_npm_install_result().unwrap_or(false)
 }
 
+/// Returns the hostname of the machine if it can be determined.
+#[cfg(not(windows))]
+pub fn get_hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    if unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) } != 0 {
+        return None;
+    }
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    Some(String::from_utf8_lossy(&buf[..len]).into_owned()).filter(|name| !name.is_empty())
+}
+
+/// Returns the hostname of the machine if it can be determined.
+#[cfg(windows)]
+pub fn get_hostname() -> Option<String> {
+    env::var("COMPUTERNAME")
+        .ok()
+        .filter(|name| !name.is_empty())
+}
+
 /// Expands variables in a string
 pub fn expand_vars<F: Fn(&str) -> String>(s: &str, f: F) -> Cow<'_, str> {
     lazy_static! {
@@ -121,7 +140,12 @@ pub fn print_error(err: &Error) {
         }
     }
 
-    if Config::current().get_log_level() < log::LevelFilter::Info {
+    // the config is not bound yet if it failed to load
+    let log_level = match Config::current_opt() {
+        Some(config) => config.get_log_level(),
+        None => log::LevelFilter::Warn,
+    };
+    if log_level < log::LevelFilter::Info {
         eprintln!();
         eprintln!("{}", style("Add --log-level=[info|debug] or export SENTRY_LOG_LEVEL=[info|debug] to see more output.").dim());
         eprintln!(
@@ -0,0 +1,123 @@
+//! Reading of tar archives in memory.
+//!
+//! Only regular files are returned.  Long names from GNU (`L`) and PAX (`x`)
+//! headers are supported, other extension headers, directories and links are
+//! skipped.
+use std::str;
+
+use failure::{bail, Error};
+
+/// Size of tar headers and the blocks file data is padded to.
+const BLOCK_SIZE: usize = 512;
+
+/// A regular file in a tar archive.
+#[derive(Debug)]
+pub struct TarEntry<'a> {
+    /// The path of the file within the archive.
+    pub name: String,
+    /// The contents of the file.
+    pub data: &'a [u8],
+}
+
+/// Returns the contents of a header field up to the first NUL byte.
+fn header_str(field: &[u8]) -> &[u8] {
+    match field.iter().position(|&b| b == 0) {
+        Some(end) => &field[..end],
+        None => field,
+    }
+}
+
+/// Parses an octal number field of a tar header.
+fn parse_octal(field: &[u8]) -> Result<usize, Error> {
+    let value = str::from_utf8(header_str(field))?.trim();
+    if value.is_empty() {
+        return Ok(0);
+    }
+    match usize::from_str_radix(value, 8) {
+        Ok(value) => Ok(value),
+        Err(_) => bail!("Invalid number '{}' in tar header", value),
+    }
+}
+
+/// Returns the `path` record of a PAX extended header.
+fn parse_pax_path(data: &[u8]) -> Option<String> {
+    // records have the form "<length> <key>=<value>\n"
+    let mut rest = data;
+    while !rest.is_empty() {
+        let space = rest.iter().position(|&b| b == b' ')?;
+        let len: usize = str::from_utf8(&rest[..space]).ok()?.parse().ok()?;
+        let record = rest.get(space + 1..len)?;
+        let record = record.strip_suffix(b"\n").unwrap_or(record);
+        if let Some(path) = record.strip_prefix(b"path=") {
+            return Some(String::from_utf8_lossy(path).into_owned());
+        }
+        rest = &rest[len..];
+    }
+    None
+}
+
+/// Reads all regular files from an uncompressed tar archive.
+pub fn read_entries(data: &[u8]) -> Result<Vec<TarEntry<'_>>, Error> {
+    let mut entries = vec![];
+    let mut long_name = None;
+    let mut offset = 0;
+
+    while offset + BLOCK_SIZE <= data.len() {
+        let header = &data[offset..offset + BLOCK_SIZE];
+        // the archive ends with zeroed blocks
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+
+        let checksum: usize = header
+            .iter()
+            .enumerate()
+            .map(|(idx, &b)| {
+                if (148..156).contains(&idx) {
+                    32
+                } else {
+                    b as usize
+                }
+            })
+            .sum();
+        if parse_octal(&header[148..156])? != checksum {
+            bail!("Invalid tar header at byte {}", offset);
+        }
+
+        let size = parse_octal(&header[124..136])?;
+        let start = offset + BLOCK_SIZE;
+        let contents = match data.get(start..start + size) {
+            Some(contents) => contents,
+            None => bail!("Unexpected end of tar archive at byte {}", data.len()),
+        };
+        offset = start + size.div_ceil(BLOCK_SIZE) * BLOCK_SIZE;
+
+        match header[156] {
+            b'0' | 0 => {
+                let name = match long_name.take() {
+                    Some(name) => name,
+                    None => {
+                        let name = String::from_utf8_lossy(header_str(&header[..100]));
+                        let prefix = header_str(&header[345..500]);
+                        if &header[257..262] == b"ustar" && !prefix.is_empty() {
+                            format!("{}/{}", String::from_utf8_lossy(prefix), name)
+                        } else {
+                            name.into_owned()
+                        }
+                    }
+                };
+                entries.push(TarEntry {
+                    name,
+                    data: contents,
+                });
+            }
+            b'L' => {
+                long_name = Some(String::from_utf8_lossy(header_str(contents)).into_owned());
+            }
+            b'x' => long_name = parse_pax_path(contents),
+            _ => long_name = None,
+        }
+    }
+
+    Ok(entries)
+}
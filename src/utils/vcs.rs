@@ -396,6 +396,49 @@ pub fn find_head() -> Result<String, Error> {
     Ok(head.id().to_string())
 }
 
+/// Returns the current branch and the SHA of `HEAD` in the local repository.
+///
+/// The branch is `None` if `HEAD` is detached.
+pub fn get_git_head() -> Result<(Option<String>, String), Error> {
+    let repo = git2::Repository::open_from_env()?;
+    let head = repo.head()?;
+    let sha = head.peel_to_commit()?.id().to_string();
+    let branch = if head.is_branch() {
+        head.shorthand().map(str::to_owned)
+    } else {
+        None
+    };
+    Ok((branch, sha))
+}
+
+/// Returns the `user.name` and `user.email` configured for git, looking at
+/// the repository configuration first and then the global one.
+pub fn get_git_user() -> (Option<String>, Option<String>) {
+    let config = match git2::Repository::open_from_env() {
+        Ok(repo) => repo.config(),
+        Err(_) => git2::Config::open_default(),
+    };
+
+    match config {
+        Ok(config) => (
+            config.get_string("user.name").ok(),
+            config.get_string("user.email").ok(),
+        ),
+        Err(err) => {
+            debug!("Could not read git config: {}", err);
+            (None, None)
+        }
+    }
+}
+
+/// Returns the summary line of a commit in the local repository, or `None` if
+/// there is no repository or it does not contain the commit.
+pub fn get_commit_summary(rev: &str) -> Option<String> {
+    let repo = git2::Repository::open_from_env().ok()?;
+    let commit = repo.revparse_single(rev).ok()?.peel_to_commit().ok()?;
+    commit.summary().map(str::to_owned)
+}
+
 /// Given commit specs, repos and remote_name this returns a list of head
 /// commits from it.
 pub fn find_heads(
@@ -1,7 +1,10 @@
+use std::io::Write;
+
 use assert_cmd::Command;
 use mockito::mock;
 use predicates::prelude::*;
 use predicates::str::contains;
+use tempfile::NamedTempFile;
 
 use crate::common;
 
@@ -39,3 +42,137 @@ fn info_fails_without_auth_token() {
         .assert()
         .failure();
 }
+
+#[test]
+fn info_uses_config_file() {
+    let _server = mock("GET", "/api/0/")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user":null,"auth":null}"#)
+        .create();
+
+    let mut config = NamedTempFile::new().unwrap();
+    writeln!(config, "[defaults]\norg = config-org").unwrap();
+
+    Command::cargo_bin("sentry-cli")
+        .unwrap()
+        .envs(common::get_base_env())
+        .env_remove("SENTRY_ORG")
+        .arg("--config")
+        .arg(config.path())
+        .arg("info")
+        .assert()
+        .success()
+        .stdout(contains("Default Organization: config-org"));
+}
+
+#[test]
+fn info_uses_config_file_after_subcommand() {
+    let _server = mock("GET", "/api/0/")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user":null,"auth":null}"#)
+        .create();
+
+    let mut config = NamedTempFile::new().unwrap();
+    writeln!(config, "[defaults]\norg = config-org").unwrap();
+
+    Command::cargo_bin("sentry-cli")
+        .unwrap()
+        .envs(common::get_base_env())
+        .env_remove("SENTRY_ORG")
+        .args(vec!["info", "--config"])
+        .arg(config.path())
+        .assert()
+        .success()
+        .stdout(contains("Default Organization: config-org"));
+}
+
+#[test]
+fn info_ignores_broken_default_config_with_config_file() {
+    let _server = mock("GET", "/api/0/")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user":null,"auth":null}"#)
+        .create();
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join(".sentryclirc"),
+        "[defaults
+",
+    )
+    .unwrap();
+    let mut config = NamedTempFile::new().unwrap();
+    writeln!(config, "[defaults]\norg = config-org").unwrap();
+
+    Command::cargo_bin("sentry-cli")
+        .unwrap()
+        .envs(common::get_base_env())
+        .current_dir(dir.path())
+        .args(vec!["info"])
+        .assert()
+        .failure();
+
+    Command::cargo_bin("sentry-cli")
+        .unwrap()
+        .envs(common::get_base_env())
+        .env_remove("SENTRY_ORG")
+        .current_dir(dir.path())
+        .args(vec!["info", "--config"])
+        .arg(config.path())
+        .assert()
+        .success()
+        .stdout(contains("Default Organization: config-org"));
+}
+
+#[test]
+fn info_uses_config_profile() {
+    let _server = mock("GET", "/api/0/")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user":null,"auth":null}"#)
+        .create();
+
+    let mut config = NamedTempFile::new().unwrap();
+    writeln!(
+        config,
+        "[defaults]\norg = default-org\n\n[profile.staging]\norg = staging-org\nproject = staging-project"
+    )
+    .unwrap();
+
+    Command::cargo_bin("sentry-cli")
+        .unwrap()
+        .envs(common::get_base_env())
+        .env("SENTRY_ORG", "env-org")
+        .args(vec!["--profile", "staging", "--config"])
+        .arg(config.path())
+        .arg("info")
+        .assert()
+        .success()
+        .stdout(
+            contains("Default Organization: staging-org")
+                .and(contains("Default Project: staging-project")),
+        );
+
+    Command::cargo_bin("sentry-cli")
+        .unwrap()
+        .envs(common::get_base_env())
+        .args(vec!["--profile", "production", "--config"])
+        .arg(config.path())
+        .arg("info")
+        .assert()
+        .failure()
+        .stderr(contains("Profile 'production' not found"));
+}
+
+#[test]
+fn info_fails_with_missing_config_file() {
+    Command::cargo_bin("sentry-cli")
+        .unwrap()
+        .envs(common::get_base_env())
+        .args(vec!["--config", "does-not-exist.ini", "info"])
+        .assert()
+        .failure()
+        .stderr(contains("Failed to load config file (does-not-exist.ini)"));
+}
@@ -1,7 +1,12 @@
 mod info;
+mod monitors;
 mod releases;
 mod releases_delete;
 mod releases_finalize;
 mod releases_info;
 mod releases_list;
 mod releases_new;
+mod send_event;
+mod send_transaction;
+mod validate_dsn;
+mod validate_event;
@@ -0,0 +1,67 @@
+use assert_cmd::Command;
+use mockito::{mock, server_url, Matcher};
+use predicates::str::is_match;
+
+use crate::common;
+
+fn check_in_command() -> Command {
+    let mut cmd = Command::cargo_bin("sentry-cli").unwrap();
+    cmd.envs(common::get_base_env())
+        .env(
+            "SENTRY_DSN",
+            format!("{}/5", server_url().replace("://", "://public@")),
+        )
+        .args(vec!["monitors", "check-in"]);
+    cmd
+}
+
+#[test]
+fn sends_check_in_envelope() {
+    let server = mock("POST", "/api/5/envelope/")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(r#""type":"check_in""#.into()),
+            Matcher::Regex(r#""monitor_slug":"nightly-backup""#.into()),
+            Matcher::Regex(r#""status":"in_progress""#.into()),
+        ]))
+        .with_status(200)
+        .with_body("{}")
+        .expect(1)
+        .create();
+
+    check_in_command()
+        .args(vec!["nightly-backup", "--status", "in_progress"])
+        .assert()
+        .success()
+        .stdout(is_match("^[0-9a-f-]{36}\n$").unwrap());
+    server.assert();
+}
+
+#[test]
+fn updates_existing_check_in() {
+    let server = mock("POST", "/api/5/envelope/")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(r#""check_in_id":"a8f2b9e4-ce8d-4d4e-9c6e-4d0e1b2f3a4c""#.into()),
+            Matcher::Regex(r#""monitor_slug":"nightly-sync""#.into()),
+            Matcher::Regex(r#""status":"error""#.into()),
+            Matcher::Regex(r#""duration":12.5"#.into()),
+        ]))
+        .with_status(200)
+        .with_body("{}")
+        .expect(1)
+        .create();
+
+    check_in_command()
+        .args(vec![
+            "nightly-sync",
+            "--status",
+            "error",
+            "--duration",
+            "12.5",
+            "--check-in-id",
+            "a8f2b9e4-ce8d-4d4e-9c6e-4d0e1b2f3a4c",
+        ])
+        .assert()
+        .success()
+        .stdout("a8f2b9e4-ce8d-4d4e-9c6e-4d0e1b2f3a4c\n");
+    server.assert();
+}
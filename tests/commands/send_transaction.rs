@@ -0,0 +1,67 @@
+use assert_cmd::Command;
+use mockito::{mock, server_url, Matcher};
+use predicates::str::contains;
+
+use crate::common;
+
+fn send_transaction_command() -> Command {
+    let mut cmd = Command::cargo_bin("sentry-cli").unwrap();
+    cmd.envs(common::get_base_env())
+        .env("SENTRY_DSN", "https://public@sentry.invalid/1")
+        .arg("send-transaction");
+    cmd
+}
+
+#[test]
+fn dry_run_prints_transaction_with_spans() {
+    send_transaction_command()
+        .args(vec![
+            "checkout",
+            "--op",
+            "http.server",
+            "--span",
+            "db.query:120",
+            "--span",
+            "render:30",
+            "--start",
+            "1600000000",
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(contains(r#""transaction": "checkout""#))
+        .stdout(contains(r#""op": "http.server""#))
+        .stdout(contains(r#""op": "db.query""#))
+        .stdout(contains(r#""start_timestamp": 1600000000.12"#))
+        .stdout(contains(r#""timestamp": "2020-09-13T12:26:40.150Z""#));
+}
+
+#[test]
+fn fails_on_invalid_span() {
+    send_transaction_command()
+        .args(vec!["checkout", "--span", "db.query", "--dry-run"])
+        .assert()
+        .failure()
+        .stderr(contains("expected op:duration_ms"));
+}
+
+#[test]
+fn sends_transaction_envelope() {
+    let server = mock("POST", "/api/1/envelope/")
+        .match_body(Matcher::Regex(r#""type":"transaction""#.into()))
+        .with_status(200)
+        .expect(1)
+        .create();
+
+    send_transaction_command()
+        .env(
+            "SENTRY_DSN",
+            format!("{}/1", server_url().replace("://", "://public@")),
+        )
+        .args(vec!["checkout", "--span", "db.query:120"])
+        .assert()
+        .success()
+        .stdout(contains("Transaction dispatched:"));
+
+    server.assert();
+}
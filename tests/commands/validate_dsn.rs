@@ -0,0 +1,47 @@
+use assert_cmd::Command;
+use mockito::{mock, server_url};
+use predicates::prelude::*;
+use predicates::str::contains;
+
+use crate::common;
+
+fn validate_dsn_command() -> Command {
+    let mut cmd = Command::cargo_bin("sentry-cli").unwrap();
+    cmd.envs(common::get_base_env())
+        .env(
+            "SENTRY_DSN",
+            format!("{}/2", server_url().replace("://", "://secret-key@")),
+        )
+        .arg("validate-dsn");
+    cmd
+}
+
+#[test]
+fn reports_accepted_dsn() {
+    let server = mock("POST", "/api/2/envelope/")
+        .with_status(200)
+        .with_header("x-sentry-rate-limits", "60:error:organization")
+        .with_body("{}")
+        .create();
+
+    validate_dsn_command().assert().success().stdout(
+        contains("Project: 2")
+            .and(contains("Rate limits: 60:error:organization"))
+            .and(contains("accepted (200)"))
+            .and(contains("secret-key").not()),
+    );
+    server.assert();
+}
+
+#[test]
+fn fails_on_unauthorized_dsn() {
+    let _server = mock("POST", "/api/2/envelope/")
+        .with_status(401)
+        .with_body("{}")
+        .create();
+
+    validate_dsn_command()
+        .assert()
+        .code(5)
+        .stdout(contains("access denied by the server (401)"));
+}
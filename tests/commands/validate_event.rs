@@ -0,0 +1,42 @@
+use std::io::Write;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use predicates::str::contains;
+use tempfile::NamedTempFile;
+
+fn validate_event_command() -> Command {
+    let mut cmd = Command::cargo_bin("sentry-cli").unwrap();
+    cmd.env_remove("SENTRY_DSN").arg("validate-event");
+    cmd
+}
+
+fn event_file(contents: &str) -> NamedTempFile {
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(contents.as_bytes()).unwrap();
+    file
+}
+
+#[test]
+fn validates_event_files_without_dsn() {
+    let file = event_file("{\"message\": \"first\"}\n{\"message\": \"second\"}\n");
+    validate_event_command()
+        .arg(file.path())
+        .assert()
+        .success()
+        .stdout(contains("ok").and(contains("(2 event(s))")));
+}
+
+#[test]
+fn fails_on_invalid_event_files() {
+    let file = event_file(&format!(
+        r#"{{"message": "hello", "tags": {{"{}": "value"}}}}"#,
+        "x".repeat(40)
+    ));
+    validate_event_command()
+        .arg(file.path())
+        .assert()
+        .failure()
+        .stdout(contains("failed").and(contains("is longer than 32 characters")))
+        .stderr(contains("1 of 1 file(s) failed validation"));
+}